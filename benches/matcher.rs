@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use word_blazer::matcher::Matcher;
+
+const LISTS: [(&str, &str); 3] = [
+    (
+        "words-simple-1000",
+        include_str!("../resources/words-simple-1000.txt"),
+    ),
+    (
+        "all-words-5200",
+        include_str!("../resources/all-words-5200.txt"),
+    ),
+    (
+        "long-words-5000",
+        include_str!("../resources/long-words-5000.txt"),
+    ),
+];
+
+/// a node of the trie of the baseline matcher.
+#[derive(Default)]
+struct TrieNode {
+    children: BTreeMap<char, usize>,
+    fallback_node: usize,
+    output: Vec<usize>,
+}
+
+/// the matcher before the transition table: it follows the fallback links at every letter
+/// and clones the output of the state it reaches.
+struct BaselineMatcher {
+    nodes: Vec<TrieNode>,
+    current: usize,
+}
+
+impl BaselineMatcher {
+    fn new(words: &[String]) -> Self {
+        let mut nodes: Vec<TrieNode> = vec![TrieNode::default()];
+        for (i, word) in words.iter().enumerate() {
+            let mut current_node: usize = 0;
+            for c in word.chars() {
+                current_node = if let Some(&next) = nodes[current_node].children.get(&c) {
+                    next
+                } else {
+                    let n: usize = nodes.len();
+                    nodes[current_node].children.insert(c, n);
+                    nodes.push(TrieNode::default());
+                    n
+                }
+            }
+            nodes[current_node].output.push(i);
+        }
+
+        let mut queue: VecDeque<usize> = nodes[0].children.values().copied().collect();
+        while let Some(parent) = queue.pop_front() {
+            for (&c, &child) in nodes[parent].children.clone().iter() {
+                let mut fallback: usize = nodes[parent].fallback_node;
+                while fallback != 0 && !nodes[fallback].children.contains_key(&c) {
+                    fallback = nodes[fallback].fallback_node
+                }
+                if let Some(&next) = nodes[fallback].children.get(&c) {
+                    fallback = next;
+                }
+                nodes[child].fallback_node = fallback;
+                let mut sub_words: Vec<usize> = nodes[fallback].output.clone();
+                nodes[child].output.append(&mut sub_words);
+                queue.push_back(child);
+            }
+        }
+        Self { nodes, current: 0 }
+    }
+
+    fn next(&mut self, c: &char) -> Vec<usize> {
+        if let Some(&next_node) = self.nodes[self.current].children.get(c) {
+            self.current = next_node;
+            return self.nodes[next_node].output.clone();
+        }
+        if self.current == 0 {
+            return vec![];
+        }
+        self.current = self.nodes[self.current].fallback_node;
+        self.next(c)
+    }
+
+    fn next_state(&self, state: usize, c: char) -> usize {
+        if let Some(&next_state) = self.nodes[state].children.get(&c) {
+            return next_state;
+        }
+        if state == 0 {
+            return 0;
+        }
+        self.next_state(self.nodes[state].fallback_node, c)
+    }
+}
+
+/// feeds every word of the list to the automaton, like a player walking over them.
+fn bench_transitions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transitions");
+    for (name, contents) in LISTS {
+        let words: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+        let text: Vec<char> = contents.chars().filter(|c| !c.is_whitespace()).collect();
+        let baseline: BaselineMatcher = BaselineMatcher::new(&words);
+        let matcher: Matcher = Matcher::new(words);
+        group.bench_with_input(
+            BenchmarkId::new("fallback links", name),
+            &text,
+            |b, text| {
+                b.iter(|| {
+                    text.iter()
+                        .fold(0, |state, &c| baseline.next_state(black_box(state), c))
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("transition table", name),
+            &text,
+            |b, text| {
+                b.iter(|| {
+                    text.iter()
+                        .fold(0, |state, &c| matcher.next_state(black_box(state), c))
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// counts the words found while reading the whole list with `next`.
+fn bench_next(c: &mut Criterion) {
+    let mut group = c.benchmark_group("next");
+    for (name, contents) in LISTS {
+        let words: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+        let text: Vec<char> = contents.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut baseline: BaselineMatcher = BaselineMatcher::new(&words);
+        let mut matcher: Matcher = Matcher::new(words);
+        group.bench_with_input(
+            BenchmarkId::new("cloned outputs", name),
+            &text,
+            |b, text| {
+                b.iter(|| {
+                    baseline.current = 0;
+                    text.iter().map(|c| baseline.next(c).len()).sum::<usize>()
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("output slices", name), &text, |b, text| {
+            b.iter(|| {
+                matcher.reset();
+                text.iter().map(|c| matcher.next(c).count()).sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_transitions, bench_next);
+criterion_main!(benches);
@@ -17,4 +17,11 @@ pub enum Action {
     GoUp,
     GoDown,
     Confirm,
+    NextExit,
+    Hint,
+    CycleExit,
+    CyclePowerUp,
+    PlaceStart,
+    ToggleMinotaur,
+    Save,
 }
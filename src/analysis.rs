@@ -0,0 +1,232 @@
+use color_eyre::Result;
+
+use crate::{
+    cache::load_matcher,
+    cli::AnalyzeArgs,
+    level::build_maze,
+    matcher::Matcher,
+    maze::{ExitKind, Maze, POWERUPS},
+    scoring::{Combo, Scorer},
+    settings::Settings,
+};
+
+/// the statistics of a generated maze.
+#[derive(Debug, PartialEq)]
+pub struct Analysis {
+    pub seed: u64,
+    pub walkable: usize,
+    pub components: usize,
+    /// the distance from the start to the closest golden exit, None if it's unreachable.
+    pub golden_distance: Option<usize>,
+    /// the distance from the start to the closest common exit, None if it's unreachable.
+    pub common_distance: Option<usize>,
+    /// the number of words that can be read along a path of the maze.
+    pub words: usize,
+    /// the number of power ups of each kind, in the order of `POWERUPS`.
+    pub power_ups: [usize; POWERUPS.len()],
+    pub best_score: usize,
+}
+
+/// prints the statistics of the mazes generated from the settings, one per seed.
+pub fn run(settings: &Settings, args: &AnalyzeArgs) -> Result<()> {
+    let mut matcher: Matcher = load_matcher(&settings.words, &settings.weights);
+    let mut settings: Settings = settings.clone();
+    let first_seed: u64 = settings.seed;
+    let csv: bool = args.csv || args.count > 1;
+    if csv {
+        println!("{}", csv_header());
+    }
+    for offset in 0..args.count as u64 {
+        settings.seed = first_seed.wrapping_add(offset);
+        let maze: Maze = build_maze(&settings, &matcher)?;
+        let analysis: Analysis = analyze(maze, &mut matcher, &settings);
+        match csv {
+            true => println!("{}", analysis.csv_row()),
+            false => print!("{}", analysis.report()),
+        }
+    }
+    Ok(())
+}
+
+/// computes the statistics of a maze.
+///
+/// # Arguments
+///
+/// * `maze` - the maze, it's played by the solver.
+/// * `matcher` - the automaton of the words, it's reset before being used.
+/// * `settings` - the settings the maze was generated with.
+pub fn analyze(maze: Maze, matcher: &mut Matcher, settings: &Settings) -> Analysis {
+    let distances: Vec<Vec<Option<usize>>> = maze.distances(maze.player_location);
+    let exit_distance = |kind: ExitKind| {
+        maze.exits
+            .iter()
+            .filter(|exit| exit.kind == kind)
+            .filter_map(|exit| distances[exit.location.0][exit.location.1])
+            .min()
+    };
+    let mut power_ups: [usize; POWERUPS.len()] = [0; POWERUPS.len()];
+    for power in maze.cells.iter().flatten().filter_map(|cell| cell.power_up) {
+        if let Some(ind) = POWERUPS.iter().position(|&p| p == power) {
+            power_ups[ind] += 1;
+        }
+    }
+    Analysis {
+        seed: settings.seed,
+        walkable: maze
+            .cells
+            .iter()
+            .flatten()
+            .filter(|cell| !cell.wall)
+            .count(),
+        components: maze.components(),
+        golden_distance: exit_distance(ExitKind::Golden),
+        common_distance: exit_distance(ExitKind::Common),
+        words: maze.words(matcher).len(),
+        power_ups,
+        best_score: best_score(maze, matcher, settings),
+    }
+}
+
+/// estimates the best score of a maze by playing it greedily: the player keeps walking to
+/// the closest word given by a hint and leaves through the exit giving the highest score
+/// at the best moment. power ups and the minotaur are ignored.
+fn best_score(mut maze: Maze, matcher: &mut Matcher, settings: &Settings) -> usize {
+    let scorer: Scorer = Scorer::from_settings(settings);
+    let mut combo: Combo = Combo::default();
+    let (mut score, mut steps): (usize, usize) = (0, settings.steps);
+    let mut best: usize = 0;
+    matcher.reset();
+    loop {
+        // an exit can be reached if the steps don't run out before stepping on it.
+        let distances: Vec<Vec<Option<usize>>> = maze.distances(maze.player_location);
+        let escape: Option<usize> = maze
+            .exits
+            .iter()
+            .filter(|exit| distances[exit.location.0][exit.location.1].is_some_and(|d| d <= steps))
+            .map(|exit| exit.kind.reward(score))
+            .max();
+        best = best.max(escape.unwrap_or_default());
+
+        let Some((path, _)) = maze.hint(matcher) else {
+            break;
+        };
+        if path.len() >= steps {
+            break;
+        }
+        for (x, y) in path {
+            maze.player_location = (x, y);
+            maze.cells[x][y].visited = true;
+            combo.step();
+            let mut found: Vec<usize> = if maze.cells[x][y].blank {
+                matcher.next_blank().collect()
+            } else {
+                matcher.next(&maze.cells[x][y].value).collect()
+            };
+            let added: usize = scorer.step_score(&mut found, &settings.words, &mut combo, 0);
+            score = score.saturating_add(added);
+            steps = steps.saturating_add(added);
+            steps -= 1;
+        }
+    }
+    best
+}
+
+/// get the names of the columns of the CSV output.
+fn csv_header() -> String {
+    let power_ups: Vec<&str> = POWERUPS.iter().map(|power| power.name()).collect();
+    format!(
+        "seed,walkable,components,golden_distance,common_distance,words,{},best_score",
+        power_ups.join(",")
+    )
+}
+
+impl Analysis {
+    /// get the statistics as a line of CSV, unreachable exits are left empty.
+    pub fn csv_row(&self) -> String {
+        let distance = |d: Option<usize>| d.map_or(String::new(), |d| d.to_string());
+        let power_ups: Vec<String> = self.power_ups.iter().map(|n| n.to_string()).collect();
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            self.seed,
+            self.walkable,
+            self.components,
+            distance(self.golden_distance),
+            distance(self.common_distance),
+            self.words,
+            power_ups.join(","),
+            self.best_score
+        )
+    }
+
+    /// get the statistics in a readable report.
+    pub fn report(&self) -> String {
+        let distance = |d: Option<usize>| d.map_or("unreachable".to_string(), |d| d.to_string());
+        let power_ups: Vec<String> = POWERUPS
+            .iter()
+            .zip(self.power_ups)
+            .map(|(power, n)| format!("{} {}", power.name(), n))
+            .collect();
+        format!(
+            "seed: {}\n\
+             walkable cells: {}\n\
+             connected components: {}\n\
+             distance to the golden exit: {}\n\
+             distance to the common exit: {}\n\
+             words along the paths: {}\n\
+             power ups: {}\n\
+             estimated best score: {}\n",
+            self.seed,
+            self.walkable,
+            self.components,
+            distance(self.golden_distance),
+            distance(self.common_distance),
+            self.words,
+            power_ups.join(", "),
+            self.best_score
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{maze::PowerUP, test_utils::test_maze};
+
+    #[test]
+    fn test_analyze() {
+        let words: Vec<String> = ["cat", "dog"].iter().map(|&s| s.to_string()).collect();
+        let settings: Settings = Settings {
+            words: words.clone(),
+            steps: 10,
+            ..Default::default()
+        };
+        let maze: Maze = test_maze(
+            &["xcat", "x#o#", "xdog"],
+            (0, 0),
+            Some((2, 0)),
+            &[((1, 0), PowerUP::AriadneThread)],
+        );
+        let mut matcher: Matcher = Matcher::new(words);
+        let analysis: Analysis = analyze(maze, &mut matcher, &settings);
+        assert_eq!(
+            analysis,
+            Analysis {
+                seed: 0,
+                walkable: 10,
+                components: 1,
+                golden_distance: Some(2),
+                common_distance: None,
+                words: 2,
+                power_ups: [1, 0, 0, 0, 0],
+                best_score: 15,
+            }
+        );
+        assert_eq!(analysis.csv_row(), "0,10,1,2,,2,1,0,0,0,0,15");
+        assert_eq!(
+            csv_header().split(',').count(),
+            analysis.csv_row().split(',').count()
+        );
+    }
+}
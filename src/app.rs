@@ -7,7 +7,7 @@ use tracing::info;
 
 use crate::{
     action::Action,
-    components::{labyrinth::Labyrinth, Component},
+    components::{editor::Editor, labyrinth::Labyrinth, level_select::LevelSelect, Component},
     config::Config,
     settings::Settings,
     tui::{Event, Tui},
@@ -30,19 +30,25 @@ pub struct App {
 pub enum Mode {
     #[default]
     Labyrinth,
+    Editor,
 }
 
 impl App {
     pub fn new(settings: Settings) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
+        let (mode, component): (Mode, Box<dyn Component>) = match settings.edit.clone() {
+            Some(path) => (Mode::Editor, Box::new(Editor::new(settings, path)?)),
+            None if settings.campaign => (Mode::Labyrinth, Box::new(LevelSelect::new(settings))),
+            None => (Mode::Labyrinth, Box::new(Labyrinth::new(settings)?)),
+        };
         Ok(Self {
             tick_rate: 4.0,
             frame_rate: 60.0,
-            components: vec![Box::new(Labyrinth::new(settings))],
+            components: vec![component],
             should_quit: false,
             should_suspend: false,
             config: Config::new()?,
-            mode: Mode::Labyrinth,
+            mode,
             last_tick_key_events: Vec::new(),
             action_tx,
             action_rx,
@@ -0,0 +1,69 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use memmap2::Mmap;
+use tracing::{info, warn};
+
+use crate::{config::get_data_dir, matcher::Matcher};
+
+/// dictionaries smaller than this are fast enough to be compiled at every launch.
+const CACHE_THRESHOLD: usize = 20_000;
+
+/// get a hash of the words and their weights that is stable between runs (FNV-1a).
+fn dictionary_hash(words: &[String], weights: &[f64]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let bytes = words
+        .iter()
+        .flat_map(|word| word.bytes().chain([b'\n']))
+        .chain(weights.iter().flat_map(|weight| weight.to_le_bytes()));
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// get the path of the precompiled automaton of a dictionary.
+fn cache_path(words: &[String], weights: &[f64]) -> PathBuf {
+    get_data_dir()
+        .join("automata")
+        .join(format!("{:016x}.bin", dictionary_hash(words, weights)))
+}
+
+/// loads the automaton of a dictionary from the data directory.
+/// large dictionaries are compiled once and cached, the next launches memory-map the cache
+/// and read the automaton and its tables in place.
+pub fn load_matcher(words: &[String], weights: &[f64]) -> Matcher {
+    if words.len() < CACHE_THRESHOLD {
+        return Matcher::with_weights(words.to_vec(), weights);
+    }
+    let path: PathBuf = cache_path(words, weights);
+    if let Ok(file) = File::open(&path) {
+        // SAFETY: the cache is only written by the game, through a rename once it's complete.
+        let loaded =
+            unsafe { Mmap::map(&file) }.and_then(|mmap| Matcher::from_buffer(Arc::new(mmap)));
+        match loaded {
+            Ok(matcher) => {
+                info!("loaded the automaton from {:?}", path);
+                return matcher;
+            }
+            Err(err) => warn!("ignoring the cached automaton {:?}: {}", path, err),
+        }
+    }
+
+    let matcher: Matcher = Matcher::with_weights(words.to_vec(), weights);
+    let temporary: PathBuf = path.with_extension("tmp");
+    let saved = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&temporary, matcher.to_bytes()))
+        .and_then(|_| fs::rename(&temporary, &path));
+    match saved {
+        Ok(()) => info!("cached the automaton in {:?}", path),
+        Err(err) => warn!("failed to cache the automaton in {:?}: {}", path, err),
+    }
+    matcher
+}
@@ -0,0 +1,189 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{config::get_data_dir, errors::LevelError, level::Level, maze::Maze};
+
+/// the levels bundled with the game, in the order they are played.
+const BUNDLED_LEVELS: [(&str, &str); 3] = [
+    (
+        "01-first-steps",
+        include_str!("../resources/levels/01-first-steps.json"),
+    ),
+    (
+        "02-blank-spaces",
+        include_str!("../resources/levels/02-blank-spaces.json"),
+    ),
+    (
+        "03-minotaur-lair",
+        include_str!("../resources/levels/03-minotaur-lair.json"),
+    ),
+];
+
+/// a level of the campaign.
+#[derive(Debug, Clone)]
+pub struct CampaignLevel {
+    /// identifies the level in the progress file.
+    pub id: String,
+    pub level: Level,
+}
+
+impl CampaignLevel {
+    /// get the name of the level, its id if it has none.
+    pub fn name(&self) -> &str {
+        self.level.name.as_deref().unwrap_or(&self.id)
+    }
+
+    /// builds the maze of the level.
+    pub fn to_maze(&self) -> Result<Maze, LevelError> {
+        self.level.to_maze()
+    }
+}
+
+/// get the directory where the players put their own levels.
+pub fn levels_dir() -> PathBuf {
+    get_data_dir().join("levels")
+}
+
+/// loads the bundled levels followed by the levels of a directory sorted by file name.
+/// the files that can't be read are skipped.
+pub fn load_levels(dir: &Path) -> Vec<CampaignLevel> {
+    let mut levels: Vec<CampaignLevel> = BUNDLED_LEVELS
+        .iter()
+        .filter_map(|(id, contents)| match serde_json::from_str(contents) {
+            Ok(level) => Some(CampaignLevel {
+                id: format!("bundled/{}", id),
+                level,
+            }),
+            Err(err) => {
+                warn!("the bundled level {} is invalid: {}", id, err);
+                None
+            }
+        })
+        .collect();
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    for path in paths {
+        match Level::load(&path) {
+            Ok(level) => levels.push(CampaignLevel {
+                id: path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                level,
+            }),
+            Err(err) => warn!("skipping the level {:?}: {:?}", path, err),
+        }
+    }
+    levels
+}
+
+/// the best score of every finished level, saved in the data directory.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    #[serde(default)]
+    pub best_scores: BTreeMap<String, usize>,
+}
+
+impl Progress {
+    /// get the path of the progress file.
+    pub fn path() -> PathBuf {
+        get_data_dir().join("campaign.json")
+    }
+
+    /// reads the progress, starting over if there is none or it can't be read.
+    pub fn load(path: &Path) -> Self {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|err| {
+            warn!("ignoring the campaign progress {:?}: {}", path, err);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .wrap_err_with(|| format!("Failed to write file: {:?}", path))
+    }
+
+    /// records the score of a finished level.
+    ///
+    /// # Returns
+    ///
+    /// whether it's the best score of the level.
+    pub fn record(&mut self, id: &str, score: usize) -> bool {
+        let improved: bool = self.best_scores.get(id).is_none_or(|&best| score > best);
+        if improved {
+            self.best_scores.insert(id.to_string(), score);
+        }
+        improved
+    }
+
+    /// checks if a level can be played, every level unlocks the next one once finished.
+    pub fn is_unlocked(&self, levels: &[CampaignLevel], index: usize) -> bool {
+        index == 0
+            || levels
+                .get(index - 1)
+                .is_some_and(|previous| self.best_scores.contains_key(&previous.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::settings::MinotaurBehavior;
+
+    #[test]
+    fn test_bundled_levels() {
+        let levels: Vec<CampaignLevel> = load_levels(Path::new("/nonexistent"));
+        assert_eq!(levels.len(), BUNDLED_LEVELS.len());
+        for level in levels.iter() {
+            if let Err(err) = level.to_maze() {
+                panic!("{} is not playable: {}", level.id, err);
+            }
+        }
+        assert_eq!(levels[0].name(), "First steps");
+        // the lair has its minotaur even on the difficulties without one.
+        assert_eq!(
+            levels[2].level.minotaur_behavior,
+            Some(MinotaurBehavior::Thief)
+        );
+
+        // a letter outside the alphabet is accepted.
+        let mut foreign: CampaignLevel = levels[0].clone();
+        foreign.level.rows[0].replace_range(1..2, "ß");
+        assert!(foreign.to_maze().is_ok());
+    }
+
+    #[test]
+    fn test_progress() {
+        let levels: Vec<CampaignLevel> = load_levels(Path::new("/nonexistent"));
+        let mut progress: Progress = Progress::default();
+        assert!(progress.is_unlocked(&levels, 0));
+        assert!(!progress.is_unlocked(&levels, 1));
+        assert!(progress.record(&levels[0].id, 40));
+        assert!(!progress.record(&levels[0].id, 10));
+        assert_eq!(progress.best_scores[&levels[0].id], 40);
+        assert!(progress.is_unlocked(&levels, 1));
+        assert!(!progress.is_unlocked(&levels, 2));
+    }
+}
@@ -1,36 +1,167 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 use crate::config::{get_config_dir, get_data_dir};
-use crate::settings::Difficulty;
+use crate::dictionary::DictFormat;
+use crate::maze::PowerUP;
+use crate::scoring::ScoringRule;
+use crate::settings::{Difficulty, Language, MinotaurBehavior, Placement, Pockets, WordList};
+use crate::theme::ThemeName;
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Difficulty of the game, this will affect the size of maze and the scarcity of the words.
-    #[arg(short, long, value_enum)]
+    #[arg(short, long, value_enum, global = true)]
     pub difficulty: Option<Difficulty>,
 
     /// The number of steps given initially to the player.
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub steps: Option<usize>,
 
     /// Height of the maze.
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub height: Option<usize>,
 
     /// Width of the maze.
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub width: Option<usize>,
 
-    /// File path for the custom words.
-    #[arg(long, short)]
-    pub path: Option<PathBuf>,
+    /// File path or bundled list name (all-words-5200, words-simple-1000, long-words-5000,
+    /// french-words, mythology, programming, animals) for the words, can be repeated. Add ":WEIGHT" to multiply the scores of
+    /// the words of that list.
+    #[arg(long, short, global = true)]
+    pub path: Vec<WordList>,
+
+    /// Theme pack changing the words, the colors and the end screens.
+    #[arg(long, value_enum, global = true)]
+    pub theme: Option<ThemeName>,
+
+    /// Format of the custom words file, by default it's guessed from the extension.
+    #[arg(long, value_enum, global = true)]
+    pub dict_format: Option<DictFormat>,
+
+    /// Language of the bundled words.
+    #[arg(long, value_enum, global = true)]
+    pub language: Option<Language>,
+
+    /// Words shorter than this are removed from the dictionary.
+    #[arg(long, global = true)]
+    pub min_length: Option<usize>,
+
+    /// Words longer than this are removed from the dictionary.
+    #[arg(long, global = true)]
+    pub max_length: Option<usize>,
+
+    /// File containing the letters that can appear in the maze, by default they are taken from the words.
+    #[arg(long, global = true)]
+    pub alphabet: Option<PathBuf>,
+
+    /// How the power ups are spread over the maze.
+    #[arg(long, value_enum, global = true)]
+    pub placement: Option<Placement>,
+
+    /// How likely a power up is to be picked compared to the others (1 by default), can be repeated.
+    #[arg(long, value_name = "POWER=WEIGHT", value_parser = parse_power_up_weight, global = true)]
+    pub power_up_weight: Vec<(PowerUP, f64)>,
+
+    /// What is done with the cells the walls cut off from the rest of the maze.
+    #[arg(long, value_enum, global = true)]
+    pub pockets: Option<Pockets>,
+
+    /// Number of blank cells, they can stand for any letter.
+    #[arg(long, global = true)]
+    pub blanks: Option<usize>,
+
+    /// Number of hints showing how to complete a nearby word.
+    #[arg(long, global = true)]
+    pub hints: Option<usize>,
 
     /// Seed this will help reproduce mazes.
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub seed: Option<u64>,
+
+    /// What the minotaur does when it catches you.
+    #[arg(long, value_enum, global = true)]
+    pub minotaur: Option<MinotaurBehavior>,
+
+    /// The rule used to score the words you find.
+    #[arg(long, value_enum, global = true)]
+    pub scoring: Option<ScoringRule>,
+
+    /// The multiplier applied to your words by each active Draupnir.
+    #[arg(long, global = true)]
+    pub draupnir: Option<usize>,
+
+    /// Play the maze saved in this file instead of generating one.
+    #[arg(long, value_name = "FILE", global = true)]
+    pub load_maze: Option<PathBuf>,
+
+    /// Save the maze in this file before playing it.
+    #[arg(long, value_name = "FILE", global = true)]
+    pub export_maze: Option<PathBuf>,
+
+    /// Play the campaign of hand-made levels, your own levels can be added to the levels folder of the data directory.
+    #[arg(long, global = true)]
+    pub campaign: bool,
+
+    /// Open the maze saved in this file in the level editor, a new maze is generated if it doesn't exist.
+    #[arg(long, value_name = "FILE", global = true)]
+    pub edit: Option<PathBuf>,
+
+    /// Print the automaton of the words up to this depth in the Graphviz DOT format and exit.
+    #[arg(long, value_name = "DEPTH", global = true)]
+    pub dump_automaton: Option<usize>,
+}
+
+/// the tools that run without starting the game.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Draw the whole maze in an SVG image.
+    Render(RenderArgs),
+    /// Print statistics about the maze, to balance the difficulties.
+    Analyze(AnalyzeArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct RenderArgs {
+    /// The file where the image is written, by default it's printed.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Highlight the shortest route from the start to each exit.
+    #[arg(long)]
+    pub route: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct AnalyzeArgs {
+    /// The number of mazes analyzed, starting from the seed and incrementing it.
+    #[arg(short = 'n', long, default_value_t = 1)]
+    pub count: usize,
+
+    /// Print the statistics as CSV, always done when several mazes are analyzed.
+    #[arg(long)]
+    pub csv: bool,
+}
+
+/// parses the weight of a power up written as "power=weight".
+fn parse_power_up_weight(s: &str) -> Result<(PowerUP, f64), String> {
+    let (name, weight) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected POWER=WEIGHT, got {:?}", s))?;
+    let power: PowerUP = PowerUP::from_str(name, true)?;
+    match weight.parse::<f64>() {
+        Ok(weight) if weight.is_finite() && weight >= 0.0 => Ok((power, weight)),
+        _ => Err(format!(
+            "invalid weight {:?}, it must be a positive number",
+            weight
+        )),
+    }
 }
 
 const VERSION_MESSAGE: &str = concat!(
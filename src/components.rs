@@ -8,7 +8,9 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{action::Action, config::Config, tui::Event};
 
+pub mod editor;
 pub mod labyrinth;
+pub mod level_select;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 ///
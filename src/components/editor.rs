@@ -0,0 +1,425 @@
+use super::Component;
+use crate::{
+    action::Action,
+    cache::load_matcher,
+    config::Config,
+    errors::LevelError,
+    level::Level,
+    matcher::Matcher,
+    maze::{maze_cell, Exit, ExitKind, Maze, MazeCell, POWERUPS},
+    settings::Settings,
+    theme::{Palette, Theme},
+};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use std::{collections::BTreeSet, path::PathBuf};
+use tokio::sync::mpsc::UnboundedSender;
+
+const HELP: &str = "arrows: move | letters, # (wall), ? (blank): paint | ctrl-e: exit | ctrl-p: power up\nctrl-t: start | ctrl-n: minotaur | ctrl-s: save | esc: quit";
+
+/// a component to draw mazes by hand and save them as levels.
+#[derive(Default)]
+pub struct Editor {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    maze: Maze,
+    /// the name, steps and minotaur behavior of the opened level, they are saved with it.
+    metadata: Option<Level>,
+    path: PathBuf,
+    palette: Palette,
+    cursor: (usize, usize),
+    /// the shortest route from the start to the first exit.
+    route: BTreeSet<(i32, i32)>,
+    status: (Color, String),
+}
+
+impl Editor {
+    /// opens the level saved in a file, or a generated maze if the file doesn't exist yet.
+    /// a level that can't be played is opened anyway, its problem is shown in the status.
+    pub fn new(settings: Settings, path: PathBuf) -> Result<Self> {
+        let (maze, metadata): (Maze, Option<Level>) = if path.exists() {
+            let level: Level = Level::load(&path)?;
+            (level.to_unchecked_maze(), Some(level))
+        } else {
+            let matcher: Matcher = load_matcher(&settings.words, &settings.weights);
+            (Maze::new(&settings, &matcher)?, None)
+        };
+        let error: Option<LevelError> = metadata.as_ref().and_then(|level| level.to_maze().err());
+        let mut editor = Self {
+            cursor: maze.player_location,
+            maze,
+            metadata,
+            path,
+            palette: Theme::from(settings.theme).palette,
+            ..Default::default()
+        };
+        editor.check();
+        if let Some(err) = error {
+            editor.status = (Color::Red, err.to_string());
+        }
+        Ok(editor)
+    }
+
+    /// get the level drawn in the editor, with the metadata of the level it was opened from.
+    fn level(&self) -> Level {
+        let level: Level = Level::from(&self.maze);
+        match &self.metadata {
+            Some(metadata) => Level {
+                name: metadata.name.clone(),
+                steps: metadata.steps,
+                minotaur_behavior: metadata.minotaur_behavior,
+                ..level
+            },
+            None => level,
+        }
+    }
+
+    /// validates the level and measures the routes from the start to the exits.
+    fn check(&mut self) {
+        self.route = BTreeSet::new();
+        match self.level().to_maze() {
+            Ok(maze) => {
+                let routes: Vec<String> = maze
+                    .exits
+                    .iter()
+                    .enumerate()
+                    .map(|(i, exit)| {
+                        let steps: usize = maze.shortest_route(i).map_or(0, |route| route.len());
+                        format!("{} exit in {} steps", exit.kind.name(), steps)
+                    })
+                    .collect();
+                self.route = maze.shortest_route(0).unwrap_or_default();
+                self.status = (Color::Green, format!("Playable: {}.", routes.join(", ")));
+            }
+            Err(err) => self.status = (Color::Red, err.to_string()),
+        }
+    }
+
+    /// saves the level if it can be played.
+    fn save(&mut self) {
+        let level: Level = self.level();
+        if let Err(err) = level.to_maze() {
+            self.status = (
+                Color::Red,
+                format!("Can't save an unplayable maze: {}", err),
+            );
+            return;
+        }
+        self.status = match level.save(&self.path) {
+            Ok(()) => (Color::Green, format!("Saved to {:?}.", self.path)),
+            Err(err) => (Color::Red, err.to_string()),
+        };
+    }
+
+    /// moves the cursor without leaving the maze.
+    fn move_cursor(&mut self, di: i32, dj: i32) {
+        let i: i32 = self.cursor.0 as i32 + di;
+        let j: i32 = self.cursor.1 as i32 + dj;
+        if i >= 0 && j >= 0 && (i as usize) < self.maze.height && (j as usize) < self.maze.width {
+            self.cursor = (i as usize, j as usize);
+        }
+    }
+
+    /// replaces the cell under the cursor, then moves to the next one like when typing.
+    fn paint(&mut self, cell: MazeCell) {
+        let (i, j) = self.cursor;
+        let old: &MazeCell = &self.maze.cells[i][j];
+        let cell: MazeCell = if cell.wall {
+            self.maze.exits.retain(|exit| exit.location != (i, j));
+            if self.maze.minotaur == Some((i, j)) {
+                self.maze.minotaur = None;
+            }
+            cell
+        } else {
+            MazeCell {
+                power_up: old.power_up,
+                exit: old.exit,
+                visited: old.visited,
+                ..cell
+            }
+        };
+        self.maze.cells[i][j] = cell;
+        self.move_cursor(0, 1);
+        self.check();
+    }
+
+    /// turns the cell under the cursor into a common exit, then a golden one, then nothing.
+    fn cycle_exit(&mut self) {
+        let (i, j) = self.cursor;
+        let next: Option<ExitKind> = match self.maze.cells[i][j].exit {
+            None => Some(ExitKind::Common),
+            Some(ExitKind::Common) => Some(ExitKind::Golden),
+            Some(ExitKind::Golden) => None,
+        };
+        self.maze.exits.retain(|exit| exit.location != (i, j));
+        if let Some(kind) = next {
+            self.maze.exits.push(Exit {
+                location: (i, j),
+                kind,
+            });
+        }
+        self.maze.cells[i][j].exit = next;
+        self.check();
+    }
+
+    /// goes through the power ups on the cell under the cursor.
+    fn cycle_power_up(&mut self) {
+        let (i, j) = self.cursor;
+        let cell: &mut MazeCell = &mut self.maze.cells[i][j];
+        cell.power_up = match cell.power_up {
+            None => Some(POWERUPS[0]),
+            Some(power) => POWERUPS
+                .iter()
+                .position(|&p| p == power)
+                .and_then(|index| POWERUPS.get(index + 1))
+                .copied(),
+        };
+        self.check();
+    }
+
+    /// moves the start of the player under the cursor.
+    fn place_start(&mut self) {
+        let (x, y) = self.maze.player_location;
+        self.maze.cells[x][y].visited = false;
+        self.maze.player_location = self.cursor;
+        self.maze.cells[self.cursor.0][self.cursor.1].visited = true;
+        self.check();
+    }
+
+    /// puts the minotaur under the cursor, or removes it if it's already there.
+    fn toggle_minotaur(&mut self) {
+        self.maze.minotaur = match self.maze.minotaur {
+            Some(location) if location == self.cursor => None,
+            _ => Some(self.cursor),
+        };
+        self.check();
+    }
+}
+
+impl Component for Editor {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Char('#') => self.paint(MazeCell::wall()),
+            KeyCode::Char('?') => self.paint(MazeCell {
+                blank: true,
+                ..Default::default()
+            }),
+            KeyCode::Char(c) if c.is_alphabetic() => {
+                let value: char = c.to_lowercase().next().unwrap_or(c);
+                self.paint(MazeCell {
+                    value,
+                    ..Default::default()
+                })
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::GoUp => self.move_cursor(-1, 0),
+            Action::GoDown => self.move_cursor(1, 0),
+            Action::GoLeft => self.move_cursor(0, -1),
+            Action::GoRight => self.move_cursor(0, 1),
+            Action::CycleExit => self.cycle_exit(),
+            Action::CyclePowerUp => self.cycle_power_up(),
+            Action::PlaceStart => self.place_start(),
+            Action::ToggleMinotaur => self.toggle_minotaur(),
+            Action::Save => self.save(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [help, status, grid] = Layout::vertical([
+            Constraint::Length(4),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        frame.render_widget(
+            Paragraph::new(HELP)
+                .block(Block::default().borders(Borders::ALL).title("Editor"))
+                .alignment(Alignment::Center),
+            help,
+        );
+        let (i, j) = self.cursor;
+        let cell: &MazeCell = &self.maze.cells[i][j];
+        let mut details: String = format!("({}, {})", i, j);
+        if let Some(power) = cell.power_up {
+            details += &format!(" {:?}", power);
+        }
+        frame.render_widget(
+            Paragraph::new(self.status.1.clone())
+                .fg(self.status.0)
+                .block(Block::default().borders(Borders::ALL).title(details))
+                .alignment(Alignment::Center),
+            status,
+        );
+
+        // only draw the part of the maze around the cursor that fits in the screen.
+        let rows: usize = (grid.height as usize).min(self.maze.height).max(1);
+        let columns: usize = (grid.width as usize / 3).min(self.maze.width).max(1);
+        let top: usize = i.saturating_sub(rows / 2).min(self.maze.height - rows);
+        let left: usize = j.saturating_sub(columns / 2).min(self.maze.width - columns);
+        let table: Table = (top..top + rows)
+            .map(|x| {
+                Row::new((left..left + columns).map(|y| {
+                    let mut cell: Cell = match self.maze.cells[x][y].exit {
+                        None if (x, y) == self.maze.player_location => Cell::new(" ◎ "),
+                        None if Some((x, y)) == self.maze.minotaur => {
+                            Cell::new(" ♞ ").fg(self.palette.minotaur)
+                        }
+                        _ => maze_cell(&self.maze.cells[x][y], &self.palette),
+                    };
+                    if self.route.contains(&(x as i32, y as i32)) {
+                        cell = cell.bg(self.palette.thread);
+                    }
+                    if (x, y) == self.cursor {
+                        cell = cell.reversed();
+                    }
+                    cell
+                }))
+            })
+            .collect();
+        frame.render_widget(
+            table
+                .column_spacing(0)
+                .widths(vec![Constraint::Length(3); columns]),
+            grid,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use std::fs;
+
+    use super::*;
+    use crate::{maze::PowerUP, settings::MinotaurBehavior, test_utils::test_maze};
+
+    fn editor(rows: &[&str]) -> Editor {
+        let mut editor: Editor = Editor {
+            maze: test_maze(rows, (0, 0), Some((0, 2)), &[]),
+            ..Default::default()
+        };
+        editor.check();
+        editor
+    }
+
+    #[test]
+    fn test_live_check() {
+        let mut editor: Editor = editor(&["aba", "bab"]);
+        assert_eq!(editor.status.0, Color::Green);
+        assert_eq!(editor.route.len(), 2);
+
+        // walling the exit off makes the maze unplayable.
+        editor.cursor = (0, 1);
+        editor.paint(MazeCell::wall());
+        editor.cursor = (1, 1);
+        editor.paint(MazeCell::wall());
+        assert_eq!(editor.cursor, (1, 2));
+        assert_eq!(editor.status.0, Color::Red);
+
+        // any letter can be painted.
+        editor.cursor = (1, 1);
+        editor.paint(MazeCell {
+            value: 'z',
+            ..Default::default()
+        });
+        assert_eq!(editor.status.0, Color::Green);
+    }
+
+    #[test]
+    fn test_open_broken_level() {
+        let path: PathBuf = std::env::temp_dir().join("word-blazer-broken-level.json");
+        fs::write(
+            &path,
+            r#"{"version": 1, "rows": ["ab1", "c"], "start": [0, 0], "exits": []}"#,
+        )
+        .unwrap();
+        let editor: Editor = Editor::new(Settings::default(), path.clone()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!((editor.maze.height, editor.maze.width), (2, 3));
+        assert!(editor.maze.cells[0][2].wall && editor.maze.cells[1][1].wall);
+        assert_eq!(
+            editor.status,
+            (Color::Red, "Row 1 has 1 cells instead of 3.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_keep_metadata() {
+        let path: PathBuf = std::env::temp_dir().join("word-blazer-metadata-level.json");
+        fs::write(
+            &path,
+            r#"{"version": 1, "name": "Lair", "steps": 7, "minotaur_behavior": "thief",
+                "rows": ["ab", "ca"], "start": [0, 0],
+                "exits": [{"location": [1, 1], "kind": "golden"}]}"#,
+        )
+        .unwrap();
+        let mut editor: Editor = Editor::new(Settings::default(), path.clone()).unwrap();
+        editor.paint(MazeCell {
+            value: 'z',
+            ..Default::default()
+        });
+        editor.save();
+        let saved: Level = Level::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(saved.name.as_deref(), Some("Lair"));
+        assert_eq!(saved.steps, Some(7));
+        assert_eq!(saved.minotaur_behavior, Some(MinotaurBehavior::Thief));
+        assert_eq!(saved.rows, vec!["zb", "ca"]);
+    }
+
+    #[test]
+    fn test_tools() {
+        let mut editor: Editor = editor(&["aba", "bab"]);
+        editor.cursor = (1, 2);
+        editor.cycle_exit();
+        editor.cycle_exit();
+        assert_eq!(editor.maze.exits[1].kind, ExitKind::Golden);
+        editor.cycle_power_up();
+        assert_eq!(
+            editor.maze.cells[1][2].power_up,
+            Some(PowerUP::AriadneThread)
+        );
+        editor.cursor = (1, 0);
+        editor.place_start();
+        assert!(!editor.maze.cells[0][0].visited);
+        assert_eq!(Level::from(&editor.maze).start, (1, 0));
+
+        // a wall can't hold an exit.
+        editor.cursor = (1, 2);
+        editor.paint(MazeCell::wall());
+        assert_eq!(editor.maze.exits.len(), 1);
+
+        let backend = ratatui::backend::TestBackend::new(40, 12);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| editor.draw(frame, frame.area()).unwrap())
+            .unwrap();
+    }
+}
@@ -1,13 +1,17 @@
 use super::Component;
 use crate::{
     action::Action,
+    cache::load_matcher,
     config::Config,
+    level::{build_maze, Level},
     matcher::Matcher,
-    maze::{Maze, MazeCell, PowerUP, VisibleArea},
-    settings::Settings,
+    maze::{ExitKind, Maze, MazeCell, PowerUP, ThreadTarget, VisibleArea},
+    scoring::{Combo, Scorer},
+    settings::{MinotaurBehavior, Settings},
+    theme::Theme,
 };
 use color_eyre::{eyre::Ok, Result};
-use rand::{thread_rng, Rng};
+use rand::{seq::SliceRandom, thread_rng};
 use ratatui::{prelude::*, widgets::*};
 use std::{
     cmp::{max, min},
@@ -15,8 +19,10 @@ use std::{
 };
 use tokio::sync::mpsc::UnboundedSender;
 
-const LOST_MESSAGE: &str = include_str!("../../resources/lost_message.txt");
-const WIN_MESSAGE: &str = include_str!("../../resources/win_message.txt");
+/// number of steps stolen by the minotaur when it catches the player.
+const MINOTAUR_THEFT: usize = 5;
+/// number of steps the minotaur rests after stealing from the player.
+const MINOTAUR_REST: usize = 3;
 
 #[derive(Default)]
 pub struct Labyrinth {
@@ -24,38 +30,73 @@ pub struct Labyrinth {
     config: Config,
     maze: Maze,
     words: Vec<String>,
+    theme: Theme,
     player_state: Vec<(usize, PowerUP)>,
     matcher: Matcher,
     visible: VisibleArea,
     score: usize,
+    scorer: Scorer,
+    combo: Combo,
     steps: usize,
     notification: (Color, String),
     notif_backup: String,
+    thread_target: ThreadTarget,
+    hints: usize,
+    minotaur: MinotaurBehavior,
+    minotaur_rest: usize,
+    caught: bool,
     lost: bool,
-    won: bool,
+    escaped: Option<ExitKind>,
 }
 
 impl Labyrinth {
-    pub fn new(settings: Settings) -> Self {
+    pub fn new(settings: Settings) -> Result<Self> {
+        let matcher: Matcher = load_matcher(&settings.words, &settings.weights);
+        let maze: Maze = build_maze(&settings, &matcher)?;
+        if let Some(path) = &settings.export_maze {
+            Level::from(&maze).save(path)?;
+        }
+        Ok(Self::with_maze(settings, matcher, maze))
+    }
+
+    /// starts a game in an existing maze.
+    pub fn with_maze(settings: Settings, matcher: Matcher, mut maze: Maze) -> Self {
+        if settings.minotaur == MinotaurBehavior::Off {
+            maze.minotaur = None;
+        }
         let mut ans = Self {
-            maze: Maze::new(&settings),
-            matcher: Matcher::new(settings.words.clone()),
+            maze,
+            matcher,
             notification: (Color::Reset, "".to_string()),
             notif_backup:
                 "Welcome to the maze:\n use <wasd> or arrows to move and <enter> to confirm move."
                     .to_string(),
             steps: settings.steps,
+            hints: settings.hints,
+            minotaur: settings.minotaur,
+            scorer: Scorer::from_settings(&settings),
             words: settings.words,
+            theme: Theme::from(settings.theme),
             ..Default::default()
         };
         ans.update_visual();
         ans
     }
 
+    /// checks if the game ended, either way.
+    pub fn is_over(&self) -> bool {
+        self.lost || self.escaped.is_some()
+    }
+
+    /// get the final score if the player escaped the maze.
+    pub fn final_score(&self) -> Option<usize> {
+        self.escaped.map(|_| self.score)
+    }
+
     /// prints the description of selected power up.
     fn show_selected(&mut self) {
         if let Some(power) = self.visible.get_powerup() {
-            self.notification.0 = power.color();
+            self.notification.0 = self.theme.palette.power_up(power);
             self.notification.1 = power.description().to_string();
         } else {
             self.notification.0 = Color::Reset;
@@ -64,27 +105,30 @@ impl Labyrinth {
     }
 
     /// handles the confirmation of a movement.
-    fn confirm(&mut self) {
+    /// returns whether the player took a step.
+    fn confirm(&mut self) -> bool {
         let x = self.visible.selected.0;
         let y = self.visible.selected.1;
         let selected_cell: &MazeCell = &self.visible.cells[x][y];
         if selected_cell.wall {
             self.notif_backup = "That's wall buddy, You're not that strong.".to_string();
-            return;
+            return false;
         }
         let center: usize = self.visible.cells.len() / 2;
         if center.abs_diff(x) > 1 || center.abs_diff(y) > 1 {
             self.notif_backup = "That's too far try something closer.".to_string();
-            return;
+            return false;
         }
 
-        // add the power up.
         let x = self.maze.player_location.0 + x - center;
         let y = self.maze.player_location.1 + y - center;
         self.maze.player_location = (x, y);
         if !self.visible.thread.remove(&(x as i32, y as i32)) {
             self.visible.thread = BTreeSet::new();
         }
+        if !self.visible.hint.remove(&(x as i32, y as i32)) {
+            self.visible.hint = BTreeSet::new();
+        }
         self.player_state = self
             .player_state
             .iter()
@@ -92,49 +136,58 @@ impl Labyrinth {
             .map(|&(l, p)| (l - 1, p))
             .collect();
 
-        if let Some(power) = self.maze.cells[x][y].power_up {
-            self.apply_power_up(power);
-        }
-
+        // the power up is applied once the letter of its cell is read.
+        let power: Option<PowerUP> = self.maze.cells[x][y].power_up;
         let current_cell: &mut MazeCell = &mut self.maze.cells[x][y];
         if current_cell.visited {
             self.matcher.reset();
+            self.combo.reset();
+            if let Some(power) = power {
+                self.apply_power_up(power);
+            }
             // check if the player lost.
             self.steps -= 1;
             if self.steps == 0 {
                 self.lost = true;
             }
-            return;
+            return true;
         }
 
         // get the score from words.
         current_cell.visited = true;
-        let found_words: Vec<String> = self
-            .matcher
-            .next(&current_cell.value)
-            .iter()
-            .map(|&ind| self.words[ind].clone())
-            .collect();
-        if !found_words.is_empty() {
-            let draupnir_bonus: usize = 8_usize.pow(
-                self.player_state
-                    .iter()
-                    .filter(|(_, p)| *p == PowerUP::OdinDraupnir)
-                    .count() as u32,
-            );
-            let mut added_score: usize = found_words.iter().map(|s| Self::score(s)).sum();
-            added_score *= draupnir_bonus;
+        self.combo.step();
+        let mut found: Vec<usize> = if current_cell.blank {
+            self.matcher.next_blank().collect()
+        } else {
+            self.matcher.next(&current_cell.value).collect()
+        };
+        let exit: Option<ExitKind> = current_cell.exit;
+        if let Some(power) = power {
+            self.apply_power_up(power);
+        }
+        if !found.is_empty() {
+            let draupnir: usize = self
+                .player_state
+                .iter()
+                .filter(|(_, p)| *p == PowerUP::OdinDraupnir)
+                .count();
+            let added_score: usize =
+                self.scorer
+                    .step_score(&mut found, &self.words, &mut self.combo, draupnir);
+            let found_words: Vec<String> =
+                found.iter().map(|&ind| self.words[ind].clone()).collect();
             self.notif_backup = "Congrats You found the word: \"".to_string()
                 + &found_words.join("\", \"")
                 + "\" and that gave you "
                 + &added_score.to_string()
                 + " steps.";
-            self.score += added_score;
-            self.steps += added_score;
+            self.score = self.score.saturating_add(added_score);
+            self.steps = self.steps.saturating_add(added_score);
         }
-        if current_cell.exit {
-            self.won = true;
-            return;
+        if let Some(kind) = exit {
+            self.score = kind.reward(self.score);
+            self.escaped = Some(kind);
+            return true;
         }
 
         // check if the player lost.
@@ -142,12 +195,95 @@ impl Labyrinth {
         if self.steps == 0 {
             self.lost = true;
         }
+        true
     }
 
-    /// calculates the score based on the length of the word.
-    fn score(s: &str) -> usize {
-        let l = s.len() + 2;
-        l * (l / 3)
+    /// lets the minotaur chase the player after each step.
+    fn hunt(&mut self) {
+        if self.lost || self.escaped.is_some() {
+            return;
+        }
+        if self.minotaur_rest > 0 {
+            self.minotaur_rest -= 1;
+            return;
+        }
+        if self.maze.minotaur != Some(self.maze.player_location) {
+            self.maze.move_minotaur();
+        }
+        if self.maze.minotaur != Some(self.maze.player_location) {
+            return;
+        }
+        match self.minotaur {
+            MinotaurBehavior::Deadly => {
+                self.caught = true;
+                self.lost = true;
+            }
+            MinotaurBehavior::Thief => {
+                let stolen: usize = min(MINOTAUR_THEFT, self.steps);
+                self.steps -= stolen;
+                self.minotaur_rest = MINOTAUR_REST;
+                self.notif_backup = format!(
+                    "The Minotaur caught you and stole {} steps, run while it rests!",
+                    stolen
+                );
+                if self.steps == 0 {
+                    self.caught = true;
+                    self.lost = true;
+                }
+            }
+            MinotaurBehavior::Off => {}
+        }
+    }
+
+    /// changes what Ariadne's thread leads to, each exit then the closest power up.
+    fn next_target(&mut self) {
+        self.thread_target = match self.thread_target {
+            ThreadTarget::Exit(exit) if exit + 1 < self.maze.exits.len() => {
+                ThreadTarget::Exit(exit + 1)
+            }
+            ThreadTarget::Exit(_) => ThreadTarget::PowerUp,
+            ThreadTarget::PowerUp => ThreadTarget::Exit(0),
+        };
+        self.notif_backup = format!("Ariadne's thread now leads to {}.", self.target_name());
+        if !self.visible.thread.is_empty() {
+            self.visible.thread = self
+                .maze
+                .ariadne_thread(self.thread_target, &self.matcher)
+                .unwrap_or_default();
+        }
+    }
+
+    /// get the name of what Ariadne's thread leads to.
+    fn target_name(&self) -> String {
+        match self.thread_target {
+            ThreadTarget::Exit(exit) => self
+                .maze
+                .exits
+                .get(exit)
+                .map_or("the exit".to_string(), |exit| {
+                    format!("the {} exit", exit.kind.name())
+                }),
+            ThreadTarget::PowerUp => "the closest power up".to_string(),
+        }
+    }
+
+    /// highlights the closest cells completing a word, if there are hints left.
+    fn hint(&mut self) {
+        if self.hints == 0 {
+            self.notif_backup = "You have no hints left, you're on your own.".to_string();
+            return;
+        }
+        let Some((path, word)) = self.maze.hint(&self.matcher) else {
+            self.notif_backup =
+                "No word can be completed from here, try somewhere else.".to_string();
+            return;
+        };
+        self.hints -= 1;
+        self.visible.hint = path.iter().map(|&(x, y)| (x as i32, y as i32)).collect();
+        self.notif_backup = format!(
+            "Follow the highlighted letters to spell \"{}\" ({} hints left).",
+            self.words[word], self.hints
+        );
     }
 
     /// regenerate the visible area based on the new position.
@@ -189,6 +325,10 @@ impl Labyrinth {
             cells: visibility_grid,
             selected: (dimention / 2, dimention / 2),
             thread: self.visible.thread.clone(),
+            hint: self.visible.hint.clone(),
+            values: self.scorer.shown_values(),
+            minotaur: self.maze.minotaur.map(|(x, y)| (x as i32, y as i32)),
+            palette: self.theme.palette,
             offset: (
                 x as i32 - sight_radius as i32,
                 y as i32 - sight_radius as i32,
@@ -198,7 +338,17 @@ impl Labyrinth {
 
     fn apply_power_up(&mut self, power: PowerUP) {
         match power {
-            PowerUP::AriadneThread => self.visible.thread = self.maze.shortest_route().unwrap(),
+            PowerUP::AriadneThread => {
+                match self.maze.ariadne_thread(self.thread_target, &self.matcher) {
+                    Some(thread) => self.visible.thread = thread,
+                    None => {
+                        self.notif_backup = format!(
+                            "Ariadne's thread can't find a way to {} from here.",
+                            self.target_name()
+                        )
+                    }
+                }
+            }
             PowerUP::ThorMjolnir => {
                 let (x, y) = self.maze.player_location;
                 let n = self.maze.height;
@@ -216,17 +366,21 @@ impl Labyrinth {
                 }
             }
             PowerUP::BifrostBridge => {
-                let mut rng = thread_rng();
-                let mut x = rng.gen_range(0..self.maze.height);
-                let mut y = rng.gen_range(0..self.maze.width);
-                self.maze.player_location = (x, y);
-                while self.maze.cells[x][y].exit || self.maze.shortest_route().is_none() {
-                    x = rng.gen_range(0..self.maze.height);
-                    y = rng.gen_range(0..self.maze.width);
+                // the player lands on a random cell from where an exit can be reached, or
+                // stays in place if there is none.
+                let exits: Vec<(usize, usize)> =
+                    self.maze.exits.iter().map(|exit| exit.location).collect();
+                let distances: Vec<Vec<Option<usize>>> = self.maze.distances_from(&exits);
+                let landings: Vec<(usize, usize)> = (0..self.maze.height)
+                    .flat_map(|x| (0..self.maze.width).map(move |y| (x, y)))
+                    .filter(|&(x, y)| {
+                        distances[x][y].is_some() && self.maze.cells[x][y].exit.is_none()
+                    })
+                    .collect();
+                if let Some(&(x, y)) = landings.choose(&mut thread_rng()) {
                     self.maze.player_location = (x, y);
+                    self.maze.cells[x][y].visited = true;
                 }
-                self.maze.cells[x][y].visited = true;
-                self.maze.cells[x][y].wall = false;
             }
             // PowerUP::ProteusGift => {},
             _ => self.player_state.push((5, power)),
@@ -259,9 +413,13 @@ impl Component for Labyrinth {
                 self.visible.selected.1 = min(self.visible.selected.1 + 1, center + 1)
             }
             Action::Confirm => {
-                self.confirm();
+                if self.confirm() {
+                    self.hunt();
+                }
                 self.update_visual();
             }
+            Action::NextExit => self.next_target(),
+            Action::Hint => self.hint(),
             _ => {}
         }
         self.show_selected();
@@ -271,7 +429,12 @@ impl Component for Labyrinth {
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         // show the lose screen.
         if self.lost {
-            let lost_board = Paragraph::new(LOST_MESSAGE)
+            let message: String = if self.caught {
+                self.theme.lost_message.to_owned() + "\nthe Minotaur caught you."
+            } else {
+                self.theme.lost_message.to_owned()
+            };
+            let lost_board = Paragraph::new(message)
                 .fg(Color::Red)
                 .block(Block::default().borders(Borders::ALL))
                 .alignment(Alignment::Center);
@@ -280,10 +443,13 @@ impl Component for Labyrinth {
         }
 
         // show the win screen.
-        if self.won {
-            let lost_board = Paragraph::new(
-                WIN_MESSAGE.to_owned() + "\nyou're score is " + &self.score.to_string(),
-            )
+        if let Some(kind) = self.escaped {
+            let lost_board = Paragraph::new(format!(
+                "{}\nyou escaped through the {} exit.\nyou're score is {}",
+                self.theme.win_message,
+                kind.name(),
+                self.score
+            ))
             .fg(Color::Green)
             .block(Block::default().borders(Borders::ALL))
             .alignment(Alignment::Center);
@@ -297,9 +463,9 @@ impl Component for Labyrinth {
         ])
         .areas(area);
 
-        // Render the score and the remaining steps.
-        let [score_area, steps_area] =
-            Layout::horizontal(Constraint::from_percentages([50, 50])).areas(score);
+        // Render the score, the word in progress and the remaining steps.
+        let [score_area, prefix_area, steps_area] =
+            Layout::horizontal(Constraint::from_ratios([(1, 3), (1, 3), (1, 3)])).areas(score);
         let steps_board = match self.steps {
             1 => Paragraph::new("You have one step remaining. Make it count!!")
                 .block(Block::default().borders(Borders::ALL))
@@ -310,10 +476,25 @@ impl Component for Labyrinth {
                 .alignment(Alignment::Center),
         };
         frame.render_widget(steps_board, steps_area);
-        let score_board = Paragraph::new(format!("Your score is {}", self.score))
-            .block(Block::default().borders(Borders::ALL))
-            .alignment(Alignment::Center);
+        let score_board = Paragraph::new(match self.combo.multiplier() {
+            1 => format!("Your score is {}", self.score),
+            combo => format!("Your score is {} (combo x{})", self.score, combo),
+        })
+        .block(Block::default().borders(Borders::ALL))
+        .alignment(Alignment::Center);
         frame.render_widget(score_board, score_area);
+        let state: usize = self.matcher.current();
+        let prefix_board = Paragraph::new(match self.matcher.depth(state) {
+            0 => "No word started yet".to_string(),
+            _ => format!(
+                "\"{}\" ({} words ahead)",
+                self.matcher.prefix(state),
+                self.matcher.reachable_words(state)
+            ),
+        })
+        .block(Block::default().borders(Borders::ALL))
+        .alignment(Alignment::Center);
+        frame.render_widget(prefix_board, prefix_area);
 
         // Render the notification board.
         let notification_board = Paragraph::new(self.notification.1.clone())
@@ -340,3 +521,151 @@ impl Component for Labyrinth {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test_utils::test_maze;
+
+    /// starts a game where the minotaur is at a location.
+    fn hunted(
+        mut maze: Maze,
+        minotaur: (usize, usize),
+        behavior: MinotaurBehavior,
+        steps: usize,
+    ) -> Labyrinth {
+        let settings: Settings = Settings {
+            minotaur: behavior,
+            steps,
+            ..Default::default()
+        };
+        maze.minotaur = Some(minotaur);
+        Labyrinth::with_maze(settings, Matcher::new(vec![]), maze)
+    }
+
+    #[test]
+    fn test_minotaur_contact() {
+        let row: Maze = test_maze(&["aaa"], (0, 0), Some((0, 2)), &[]);
+        let mut deadly: Labyrinth = hunted(row.clone(), (0, 1), MinotaurBehavior::Deadly, 10);
+        deadly.hunt();
+        assert!(deadly.caught && deadly.lost);
+
+        let mut thief: Labyrinth = hunted(row.clone(), (0, 1), MinotaurBehavior::Thief, 10);
+        thief.hunt();
+        assert_eq!(thief.steps, 10 - MINOTAUR_THEFT);
+        assert!(!thief.caught && !thief.lost);
+        // the minotaur rests after stealing.
+        thief.hunt();
+        assert_eq!(thief.steps, 10 - MINOTAUR_THEFT);
+        assert_eq!(thief.minotaur_rest, MINOTAUR_REST - 1);
+
+        let mut ruined: Labyrinth = hunted(row, (0, 1), MinotaurBehavior::Thief, 3);
+        ruined.hunt();
+        assert_eq!(ruined.steps, 0);
+        assert!(ruined.caught && ruined.lost);
+    }
+
+    #[test]
+    fn test_minotaur_after_mjolnir() {
+        let maze: Maze = test_maze(&["a#a", "a#a", "a#a"], (0, 0), Some((2, 0)), &[]);
+        let mut labyrinth: Labyrinth = hunted(maze, (0, 2), MinotaurBehavior::Deadly, 10);
+        labyrinth.hunt();
+        assert_eq!(labyrinth.maze.minotaur, Some((0, 2)));
+        labyrinth.apply_power_up(PowerUP::ThorMjolnir);
+        labyrinth.hunt();
+        assert_eq!(labyrinth.maze.minotaur, Some((1, 1)));
+        labyrinth.hunt();
+        assert!(labyrinth.caught);
+    }
+
+    #[test]
+    fn test_word_without_origin() {
+        let words: Vec<String> = vec!["b".to_string()];
+        let settings: Settings = Settings {
+            words: words.clone(),
+            steps: 10,
+            minotaur: MinotaurBehavior::Off,
+            ..Default::default()
+        };
+        let maze: Maze = test_maze(&["abb"], (0, 0), Some((0, 2)), &[]);
+        let mut labyrinth: Labyrinth = Labyrinth::with_maze(settings, Matcher::new(words), maze);
+        let center: usize = labyrinth.visible.cells.len() / 2;
+        labyrinth.visible.selected = (center, center + 1);
+        assert!(labyrinth.confirm());
+        assert!(labyrinth.score > 0);
+    }
+
+    #[test]
+    fn test_thread_pickup() {
+        let maze: Maze = test_maze(
+            &["aaaaa"],
+            (0, 0),
+            Some((0, 4)),
+            &[
+                ((0, 1), PowerUP::AriadneThread),
+                ((0, 3), PowerUP::HeliosTorch),
+            ],
+        );
+        let mut labyrinth: Labyrinth = hunted(maze, (0, 0), MinotaurBehavior::Off, 10);
+        labyrinth.thread_target = ThreadTarget::PowerUp;
+        let center: usize = labyrinth.visible.cells.len() / 2;
+        labyrinth.visible.selected = (center, center + 1);
+        assert!(labyrinth.confirm());
+        assert_eq!(labyrinth.visible.thread, BTreeSet::from([(0, 2), (0, 3)]));
+    }
+
+    #[test]
+    fn test_bifrost() {
+        let maze: Maze = test_maze(&["a#aa"], (0, 0), Some((0, 2)), &[]);
+        let mut labyrinth: Labyrinth = hunted(maze, (0, 0), MinotaurBehavior::Off, 10);
+        labyrinth.apply_power_up(PowerUP::BifrostBridge);
+        assert_eq!(labyrinth.maze.player_location, (0, 3));
+        assert!(labyrinth.maze.cells[0][3].visited);
+
+        // without an exit to reach the player stays in place.
+        labyrinth.maze.exits.clear();
+        labyrinth.apply_power_up(PowerUP::BifrostBridge);
+        assert_eq!(labyrinth.maze.player_location, (0, 3));
+    }
+
+    #[test]
+    fn test_next_target() {
+        let settings: Settings = Settings {
+            height: 20,
+            width: 20,
+            minotaur: MinotaurBehavior::Off,
+            ..Default::default()
+        };
+        let matcher: Matcher = Matcher::new(vec![]);
+        let maze: Maze = Maze::new(&settings, &matcher).unwrap();
+        let mut labyrinth: Labyrinth = Labyrinth::with_maze(settings, matcher, maze);
+        let mut targets: Vec<(ThreadTarget, String)> = vec![];
+        for _ in 0..4 {
+            labyrinth.next_target();
+            targets.push((labyrinth.thread_target, labyrinth.notif_backup.clone()));
+        }
+        assert_eq!(
+            targets,
+            vec![
+                (
+                    ThreadTarget::Exit(1),
+                    "Ariadne's thread now leads to the common exit.".to_string()
+                ),
+                (
+                    ThreadTarget::PowerUp,
+                    "Ariadne's thread now leads to the closest power up.".to_string()
+                ),
+                (
+                    ThreadTarget::Exit(0),
+                    "Ariadne's thread now leads to the golden exit.".to_string()
+                ),
+                (
+                    ThreadTarget::Exit(1),
+                    "Ariadne's thread now leads to the common exit.".to_string()
+                ),
+            ]
+        );
+    }
+}
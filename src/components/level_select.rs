@@ -0,0 +1,226 @@
+use super::{labyrinth::Labyrinth, Component};
+use crate::{
+    action::Action,
+    cache::load_matcher,
+    campaign::{levels_dir, load_levels, CampaignLevel, Progress},
+    config::Config,
+    maze::Maze,
+    settings::Settings,
+};
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use std::path::PathBuf;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// the level select screen of the campaign, it runs the chosen levels.
+#[derive(Default)]
+pub struct LevelSelect {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    settings: Settings,
+    levels: Vec<CampaignLevel>,
+    progress: Progress,
+    progress_path: PathBuf,
+    selected: usize,
+    /// the index of the level being played and its game.
+    playing: Option<(usize, Labyrinth)>,
+    notification: (Color, String),
+}
+
+impl LevelSelect {
+    pub fn new(settings: Settings) -> Self {
+        let progress_path: PathBuf = Progress::path();
+        Self {
+            levels: load_levels(&levels_dir()),
+            progress: Progress::load(&progress_path),
+            progress_path,
+            settings,
+            notification: (
+                Color::Reset,
+                format!(
+                    "Pick a level with <wasd> or arrows and <enter>, add your own levels in {:?}.",
+                    levels_dir()
+                ),
+            ),
+            ..Default::default()
+        }
+    }
+
+    /// starts the selected level if it's unlocked.
+    fn start(&mut self) {
+        if self.levels.is_empty() {
+            return;
+        }
+        if !self.progress.is_unlocked(&self.levels, self.selected) {
+            self.notification = (
+                Color::Red,
+                "This level is locked, finish the previous one first.".to_string(),
+            );
+            return;
+        }
+        let level: &CampaignLevel = &self.levels[self.selected];
+        let maze: Maze = match level.to_maze() {
+            Ok(maze) => maze,
+            Err(err) => {
+                self.notification = (Color::Red, format!("{} can't be played: {}", level.id, err));
+                return;
+            }
+        };
+        let mut settings: Settings = self.settings.clone();
+        if let Some(steps) = level.level.steps {
+            settings.steps = steps;
+        }
+        if let Some(behavior) = level.level.minotaur_behavior {
+            settings.minotaur = behavior;
+        }
+        let matcher = load_matcher(&settings.words, &settings.weights);
+        let mut game: Labyrinth = Labyrinth::with_maze(settings, matcher, maze);
+        if let Err(err) = game.register_config_handler(self.config.clone()) {
+            self.notification = (Color::Red, err.to_string());
+            return;
+        }
+        self.playing = Some((self.selected, game));
+    }
+
+    /// records the result of the finished game and goes back to the level select.
+    fn finish(&mut self) {
+        let Some((index, game)) = self.playing.take() else {
+            return;
+        };
+        let level: &CampaignLevel = &self.levels[index];
+        let Some(score) = game.final_score() else {
+            self.notification = (Color::Red, format!("{} beat you, try again!", level.name()));
+            return;
+        };
+        let best: bool = self.progress.record(&level.id, score);
+        self.notification = match self.progress.save(&self.progress_path) {
+            Err(err) => (Color::Red, err.to_string()),
+            Ok(()) if best => (
+                Color::Green,
+                format!("New best score on {}: {}!", level.name(), score),
+            ),
+            Ok(()) => (
+                Color::Green,
+                format!("You finished {} with {} points.", level.name(), score),
+            ),
+        };
+        if index + 1 < self.levels.len() {
+            self.selected = index + 1;
+        }
+    }
+}
+
+impl Component for LevelSelect {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Some((_, game)) = self.playing.as_mut() {
+            if game.is_over() && action == Action::Confirm {
+                self.finish();
+                return Ok(None);
+            }
+            return game.update(action);
+        }
+        match action {
+            Action::GoUp => self.selected = self.selected.saturating_sub(1),
+            Action::GoDown => {
+                self.selected = (self.selected + 1).min(self.levels.len().saturating_sub(1))
+            }
+            Action::Confirm => self.start(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Some((_, game)) = self.playing.as_mut() {
+            if !game.is_over() {
+                return game.draw(frame, area);
+            }
+            let [end_screen, hint] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+            game.draw(frame, end_screen)?;
+            frame.render_widget(
+                Paragraph::new("Press <enter> to go back to the levels.")
+                    .alignment(Alignment::Center),
+                hint,
+            );
+            return Ok(());
+        }
+
+        let [notif, list] =
+            Layout::vertical([Constraint::Length(4), Constraint::Fill(1)]).areas(area);
+        if self.levels.is_empty() {
+            frame.render_widget(
+                Paragraph::new(format!(
+                    "No level could be loaded, add your own levels in {:?}.",
+                    levels_dir()
+                ))
+                .fg(Color::Red)
+                .wrap(Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL).title("Campaign"))
+                .alignment(Alignment::Center),
+                area,
+            );
+            return Ok(());
+        }
+        frame.render_widget(
+            Paragraph::new(self.notification.1.clone())
+                .fg(self.notification.0)
+                .wrap(Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL).title("Campaign"))
+                .alignment(Alignment::Center),
+            notif,
+        );
+        let items: Vec<ListItem> = self
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(i, level)| {
+                let status: String = match self.progress.best_scores.get(&level.id) {
+                    Some(score) => format!("best score {}", score),
+                    None if self.progress.is_unlocked(&self.levels, i) => "new".to_string(),
+                    None => "locked".to_string(),
+                };
+                let item = ListItem::new(format!("{:>2}. {:<30} {}", i + 1, level.name(), status));
+                if self.progress.is_unlocked(&self.levels, i) {
+                    item
+                } else {
+                    item.fg(Color::DarkGray)
+                }
+            })
+            .collect();
+        let mut state: ListState = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::default().borders(Borders::ALL).title("Levels"))
+                .highlight_symbol("> ")
+                .highlight_style(Style::default().reversed()),
+            list,
+            &mut state,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_levels() {
+        let mut select: LevelSelect = LevelSelect::default();
+        select.update(Action::GoDown).unwrap();
+        select.update(Action::Confirm).unwrap();
+        assert_eq!(select.selected, 0);
+        assert!(select.playing.is_none());
+    }
+}
@@ -0,0 +1,431 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs::File,
+    io::Read,
+    path::Path,
+};
+
+use clap::ValueEnum;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use strum::Display;
+use unicode_normalization::UnicodeNormalization;
+
+/// the magic bytes at the start of gzip files.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// the supported formats of word lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DictFormat {
+    /// one word per line.
+    Plain,
+    /// hunspell dictionary, the affix flags after '/' are ignored.
+    Hunspell,
+    /// comma separated values with a word and an optional weight per line.
+    Csv,
+    /// an array of words, of [word, weight] pairs or of {"word", "weight"} objects,
+    /// or an object mapping words to weights.
+    Json,
+}
+
+impl DictFormat {
+    /// guesses the format from the extension of the file, ignoring a trailing ".gz".
+    pub fn from_path(path: &Path) -> Self {
+        let name: String = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let name: &str = name.strip_suffix(".gz").unwrap_or(&name);
+        match name.rsplit_once('.').map(|(_, extension)| extension) {
+            Some("dic") => DictFormat::Hunspell,
+            Some("csv") => DictFormat::Csv,
+            Some("json") => DictFormat::Json,
+            _ => DictFormat::Plain,
+        }
+    }
+
+    /// splits the contents of a word list into entries with an optional weight.
+    pub fn parse(&self, contents: &str) -> Result<Vec<(String, Option<f64>)>> {
+        let entries: Vec<(String, Option<f64>)> = match self {
+            DictFormat::Plain => contents
+                .lines()
+                .map(|line| (line.to_string(), None))
+                .collect(),
+            DictFormat::Hunspell => contents
+                .lines()
+                .enumerate()
+                // the first line holds the number of words.
+                .filter(|(i, line)| !(*i == 0 && line.trim().parse::<usize>().is_ok()))
+                .map(|(_, line)| {
+                    let word: &str = line.split('/').next().unwrap_or_default();
+                    (word.to_string(), None)
+                })
+                .collect(),
+            DictFormat::Csv => contents
+                .lines()
+                .enumerate()
+                .filter_map(|(i, line)| {
+                    let mut fields = line.split(',');
+                    let word: String = fields.next().unwrap_or_default().trim().to_string();
+                    let weight: Option<&str> = fields.next().map(str::trim);
+                    match weight.map(str::parse::<f64>) {
+                        // a first line without a numeric weight is the header.
+                        Some(Err(_)) if i == 0 => None,
+                        Some(Err(_)) => Some((word, Some(f64::NAN))),
+                        Some(Ok(weight)) => Some((word, Some(weight))),
+                        None => Some((word, None)),
+                    }
+                })
+                .collect(),
+            DictFormat::Json => {
+                let value: Value =
+                    serde_json::from_str(contents).wrap_err("Invalid json word list")?;
+                match value {
+                    Value::Array(items) => items
+                        .into_iter()
+                        .map(|item| match item {
+                            Value::String(word) => Ok((word, None)),
+                            Value::Array(pair) => match pair.as_slice() {
+                                [Value::String(word), weight] => {
+                                    Ok((word.clone(), Some(weight.as_f64().unwrap_or(f64::NAN))))
+                                }
+                                _ => Err(eyre!("Invalid json entry: {:?}", pair)),
+                            },
+                            Value::Object(object) => match object.get("word") {
+                                Some(Value::String(word)) => Ok((
+                                    word.clone(),
+                                    object.get("weight").map(|w| w.as_f64().unwrap_or(f64::NAN)),
+                                )),
+                                _ => Err(eyre!("Invalid json entry: {:?}", object)),
+                            },
+                            other => Err(eyre!("Invalid json entry: {}", other)),
+                        })
+                        .collect::<Result<_>>()?,
+                    Value::Object(object) => object
+                        .into_iter()
+                        .map(|(word, weight)| (word, Some(weight.as_f64().unwrap_or(f64::NAN))))
+                        .collect(),
+                    other => return Err(eyre!("Expected a json array or object, got {}", other)),
+                }
+            }
+        };
+        Ok(entries)
+    }
+}
+
+/// reads the entries of a word list, gzip compressed files are decompressed first.
+///
+/// # Arguments
+///
+/// * `path` - the path of the word list.
+/// * `format` - the format of the list, guessed from the extension if None.
+pub fn read_entries(path: &Path, format: Option<DictFormat>) -> Result<Vec<(String, Option<f64>)>> {
+    let mut bytes: Vec<u8> = vec![];
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .wrap_err_with(|| format!("Failed to read file: {:?}", path))?;
+    let contents: String = if bytes.starts_with(&GZIP_MAGIC) {
+        let mut contents: String = String::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_string(&mut contents)
+            .wrap_err_with(|| format!("Failed to decompress file: {:?}", path))?;
+        contents
+    } else {
+        String::from_utf8(bytes).wrap_err_with(|| format!("File is not utf-8: {:?}", path))?
+    };
+    format
+        .unwrap_or_else(|| DictFormat::from_path(path))
+        .parse(&contents)
+}
+
+/// the rules a word has to follow to enter the dictionary.
+#[derive(Default, Clone)]
+pub struct DictionaryRules {
+    pub min_len: usize,
+    pub max_len: Option<usize>,
+    pub alphabet: Option<BTreeSet<char>>,
+}
+
+/// the reasons a word can be rejected from the dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+pub enum Rejection {
+    #[strum(to_string = "contain characters that are not letters")]
+    NotALetter,
+    #[strum(to_string = "use letters outside of the alphabet")]
+    Alphabet,
+    #[strum(to_string = "are too short")]
+    TooShort,
+    #[strum(to_string = "are too long")]
+    TooLong,
+    #[strum(to_string = "have an invalid weight")]
+    Weight,
+    #[strum(to_string = "are duplicates")]
+    Duplicate,
+}
+
+/// the cleaned words of a word list and the entries that were rejected.
+/// the weights are empty unless at least one entry had a weight.
+/// the origins hold the index of the list each word came from.
+#[derive(Default)]
+pub struct Dictionary {
+    pub words: Vec<String>,
+    pub weights: Vec<f64>,
+    pub origins: Vec<usize>,
+    pub rejected: Vec<(String, Rejection)>,
+}
+
+impl Dictionary {
+    /// cleans the entries of a word list, entries without a weight weigh one.
+    /// comments start with '#', blank lines are ignored, the words are lowercased, normalized
+    /// to NFC and the punctuation around them is removed.
+    pub fn new<'a>(
+        entries: impl IntoIterator<Item = (&'a str, Option<f64>)>,
+        rules: &DictionaryRules,
+    ) -> Self {
+        let mut dictionary: Dictionary = Dictionary::default();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut weighted: bool = false;
+        for (line, weight) in entries {
+            let entry: &str = line.split('#').next().unwrap_or_default().trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let word: String = entry
+                .trim_matches(|c: char| c.is_ascii_punctuation())
+                .to_lowercase()
+                .nfc()
+                .collect();
+            let len: usize = word.chars().count();
+            let rejection: Option<Rejection> =
+                if word.is_empty() || !word.chars().all(char::is_alphabetic) {
+                    Some(Rejection::NotALetter)
+                } else if rules
+                    .alphabet
+                    .as_ref()
+                    .is_some_and(|alphabet| !word.chars().all(|c| alphabet.contains(&c)))
+                {
+                    Some(Rejection::Alphabet)
+                } else if len < rules.min_len {
+                    Some(Rejection::TooShort)
+                } else if rules.max_len.is_some_and(|max_len| len > max_len) {
+                    Some(Rejection::TooLong)
+                } else if weight.is_some_and(|w| !w.is_finite() || w <= 0.0) {
+                    Some(Rejection::Weight)
+                } else if seen.contains(&word) {
+                    Some(Rejection::Duplicate)
+                } else {
+                    None
+                };
+            match rejection {
+                Some(rejection) => dictionary.rejected.push((entry.to_string(), rejection)),
+                None => {
+                    seen.insert(word.clone());
+                    dictionary.words.push(word);
+                    dictionary.weights.push(weight.unwrap_or(1.0));
+                    dictionary.origins.push(0);
+                    weighted |= weight.is_some();
+                }
+            }
+        }
+        if !weighted {
+            dictionary.weights.clear();
+        }
+        dictionary
+    }
+
+    /// rejects the words of a list that are already in one of the lists before it.
+    pub fn reject_duplicates(lists: &mut [Dictionary]) {
+        let mut seen: HashSet<String> = HashSet::new();
+        for list in lists.iter_mut() {
+            let mut kept: Dictionary = Dictionary {
+                rejected: std::mem::take(&mut list.rejected),
+                ..Default::default()
+            };
+            for (i, word) in std::mem::take(&mut list.words).into_iter().enumerate() {
+                if seen.contains(&word) {
+                    kept.rejected.push((word, Rejection::Duplicate));
+                    continue;
+                }
+                seen.insert(word.clone());
+                kept.words.push(word);
+                kept.origins.push(list.origins[i]);
+                if let Some(&weight) = list.weights.get(i) {
+                    kept.weights.push(weight);
+                }
+            }
+            *list = kept;
+        }
+    }
+
+    /// merges the dictionaries of several word lists, a word stays in the first list it's in.
+    pub fn merge(mut lists: Vec<Dictionary>) -> Self {
+        Self::reject_duplicates(&mut lists);
+        let weighted: bool = lists.iter().any(|list| !list.weights.is_empty());
+        let mut merged: Dictionary = Dictionary::default();
+        for (origin, list) in lists.into_iter().enumerate() {
+            merged.rejected.extend(list.rejected);
+            for (i, word) in list.words.into_iter().enumerate() {
+                merged.words.push(word);
+                merged.origins.push(origin);
+                if weighted {
+                    merged
+                        .weights
+                        .push(list.weights.get(i).copied().unwrap_or(1.0));
+                }
+            }
+        }
+        merged
+    }
+
+    /// get a human readable summary of the rejected entries.
+    ///
+    /// # Returns
+    ///
+    /// None if no entry was rejected.
+    pub fn summary(&self) -> Option<String> {
+        if self.rejected.is_empty() {
+            return None;
+        }
+        let mut by_reason: BTreeMap<Rejection, Vec<&str>> = BTreeMap::new();
+        for (entry, rejection) in self.rejected.iter() {
+            by_reason.entry(*rejection).or_default().push(entry);
+        }
+        let mut summary: String = format!(
+            "{} of {} entries were rejected from the dictionary:",
+            self.rejected.len(),
+            self.rejected.len() + self.words.len()
+        );
+        for (rejection, entries) in by_reason {
+            let examples: Vec<&str> = entries.iter().take(3).copied().collect();
+            summary += &format!(
+                "\n  {} {} (\"{}\"{})",
+                entries.len(),
+                rejection,
+                examples.join("\", \""),
+                if entries.len() > examples.len() {
+                    ", ..."
+                } else {
+                    ""
+                }
+            );
+        }
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_normalization() {
+        let lines = [
+            "# comment",
+            "",
+            "Word ",
+            "word",
+            "don't",
+            "fire, # hot",
+            "a",
+            "née",
+            "ne\u{301}e",
+        ];
+        let rules: DictionaryRules = DictionaryRules {
+            min_len: 2,
+            ..Default::default()
+        };
+        let dictionary: Dictionary = Dictionary::new(lines.map(|line| (line, None)), &rules);
+        assert_eq!(dictionary.words, vec!["word", "fire", "née"]);
+        let rejections: Vec<Rejection> = dictionary.rejected.iter().map(|(_, r)| *r).collect();
+        assert_eq!(
+            rejections,
+            vec![
+                Rejection::Duplicate,
+                Rejection::NotALetter,
+                Rejection::TooShort,
+                Rejection::Duplicate
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alphabet_and_max_len() {
+        let rules: DictionaryRules = DictionaryRules {
+            min_len: 1,
+            max_len: Some(4),
+            alphabet: Some(('a'..='z').collect()),
+        };
+        let entries = ["née", "fires", "love"].map(|word| (word, None));
+        let dictionary: Dictionary = Dictionary::new(entries, &rules);
+        assert_eq!(dictionary.words, vec!["love"]);
+        assert!(dictionary.weights.is_empty());
+        assert!(dictionary.summary().is_some());
+    }
+
+    #[test]
+    fn test_merge() {
+        let rules: DictionaryRules = DictionaryRules::default();
+        let first: Dictionary = Dictionary::new([("fire", None), ("love", None)], &rules);
+        let second: Dictionary = Dictionary::new([("love", Some(2.0)), ("word", None)], &rules);
+        let mut lists: Vec<Dictionary> = vec![first, second];
+        Dictionary::reject_duplicates(&mut lists);
+        assert_eq!(lists[1].words, vec!["word"]);
+        assert_eq!(lists[1].weights, vec![1.0]);
+        assert!(lists[1]
+            .summary()
+            .is_some_and(|summary| summary.contains("love")));
+        let merged: Dictionary = Dictionary::merge(lists);
+        assert_eq!(merged.words, vec!["fire", "love", "word"]);
+        assert_eq!(merged.origins, vec![0, 0, 1]);
+        assert_eq!(merged.weights, vec![1.0, 1.0, 1.0]);
+        assert_eq!(
+            merged.rejected,
+            vec![("love".to_string(), Rejection::Duplicate)]
+        );
+    }
+
+    #[test]
+    fn test_formats() {
+        let hunspell = DictFormat::Hunspell.parse("2\nfire/S\nlove/DSG").unwrap();
+        assert_eq!(
+            hunspell,
+            vec![("fire".to_string(), None), ("love".to_string(), None)]
+        );
+
+        let csv = DictFormat::Csv
+            .parse("word,weight\nfire,2.5\nlove")
+            .unwrap();
+        assert_eq!(
+            csv,
+            vec![("fire".to_string(), Some(2.5)), ("love".to_string(), None)]
+        );
+
+        let json = DictFormat::Json
+            .parse(r#"["fire", ["love", 2], {"word": "word", "weight": 0.5}]"#)
+            .unwrap();
+        assert_eq!(
+            json,
+            vec![
+                ("fire".to_string(), None),
+                ("love".to_string(), Some(2.0)),
+                ("word".to_string(), Some(0.5))
+            ]
+        );
+
+        let dictionary: Dictionary = Dictionary::new(
+            csv.iter().map(|(word, weight)| (word.as_str(), *weight)),
+            &DictionaryRules::default(),
+        );
+        assert_eq!(dictionary.weights, vec![2.5, 1.0]);
+        assert_eq!(
+            DictFormat::from_path(Path::new("words.csv.gz")),
+            DictFormat::Csv
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, fmt};
 
 use color_eyre::Result;
 use tracing::error;
@@ -49,6 +49,139 @@ pub fn init() -> Result<()> {
     Ok(())
 }
 
+/// the reasons a maze can't be generated from the settings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerationError {
+    /// there are more power ups to place than free cells.
+    TooManyPowerUps { requested: usize, available: usize },
+    /// the weights of the power ups can't be used to pick them.
+    InvalidPowerUpWeights,
+    /// no walkable cell can be reached from the golden exit to start from.
+    NoStart,
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerationError::TooManyPowerUps {
+                requested,
+                available,
+            } => write!(
+                f,
+                "{} power ups were requested but only {} cells can hold one",
+                requested, available
+            ),
+            GenerationError::InvalidPowerUpWeights => {
+                write!(f, "the power up weights must be positive and not all zero")
+            }
+            GenerationError::NoStart => write!(
+                f,
+                "no cell can be reached from the golden exit, the maze has too many walls"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenerationError {}
+
+/// the reasons a level can't be played.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelError {
+    UnsupportedVersion(u32),
+    Empty,
+    /// a row doesn't have as many cells as the first one.
+    RaggedRow {
+        row: usize,
+        cells: usize,
+        expected: usize,
+    },
+    NotALetter {
+        letter: char,
+        location: (usize, usize),
+    },
+    /// a letter can't be read by the dictionary of the game.
+    NotInAlphabet {
+        letter: char,
+        location: (usize, usize),
+    },
+    /// the level gives no step to the player.
+    NoSteps,
+    StartNotOpen((usize, usize)),
+    NoExit,
+    ExitOutside((usize, usize)),
+    ExitNotOnLetter((usize, usize)),
+    /// two exits are on the same cell.
+    DuplicateExit((usize, usize)),
+    PowerUpNotOpen((usize, usize)),
+    MinotaurNotOpen((usize, usize)),
+    UnreachableExit((usize, usize)),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported maze file version {}.", version)
+            }
+            LevelError::Empty => write!(f, "The maze is empty."),
+            LevelError::RaggedRow {
+                row,
+                cells,
+                expected,
+            } => write!(
+                f,
+                "Row {} has {} cells instead of {}.",
+                row, cells, expected
+            ),
+            LevelError::NotALetter {
+                letter,
+                location: (i, j),
+            } => write!(
+                f,
+                "The character {:?} at ({}, {}) is not a letter.",
+                letter, i, j
+            ),
+            LevelError::NotInAlphabet {
+                letter,
+                location: (i, j),
+            } => write!(
+                f,
+                "The letter {:?} at ({}, {}) is not in the alphabet.",
+                letter, i, j
+            ),
+            LevelError::NoSteps => write!(f, "The level must give at least one step."),
+            LevelError::StartNotOpen(start) => {
+                write!(f, "The start {:?} is not an open cell of the maze.", start)
+            }
+            LevelError::NoExit => write!(f, "The maze has no exit."),
+            LevelError::ExitOutside(exit) => {
+                write!(
+                    f,
+                    "The exit {:?} is outside the maze or on the start.",
+                    exit
+                )
+            }
+            LevelError::ExitNotOnLetter(exit) => {
+                write!(f, "The exit {:?} must be on a letter.", exit)
+            }
+            LevelError::DuplicateExit(exit) => {
+                write!(f, "There is more than one exit at {:?}.", exit)
+            }
+            LevelError::PowerUpNotOpen(location) => {
+                write!(f, "The power up at {:?} is not on an open cell.", location)
+            }
+            LevelError::MinotaurNotOpen(location) => {
+                write!(f, "The minotaur at {:?} is not on an open cell.", location)
+            }
+            LevelError::UnreachableExit(exit) => {
+                write!(f, "The exit {:?} can't be reached from the start.", exit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
 /// Similar to the `std::dbg!` macro, but generates `tracing` events rather
 /// than printing to stdout.
 ///
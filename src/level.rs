@@ -0,0 +1,366 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use color_eyre::{eyre::WrapErr, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::LevelError,
+    matcher::Matcher,
+    maze::{Exit, Maze, MazeCell, PowerUP},
+    settings::{MinotaurBehavior, Settings},
+};
+
+/// the version of the maze file format.
+const LEVEL_VERSION: u32 = 1;
+/// the character of the walls in the rows of a level.
+const WALL: char = '#';
+/// the character of the blanks in the rows of a level.
+const BLANK: char = '?';
+
+/// a power up and its location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlacedPowerUp {
+    pub location: (usize, usize),
+    pub power: PowerUP,
+}
+
+/// a maze saved as json, the letters are written row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Level {
+    pub version: u32,
+    /// the name shown in the campaign.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// the number of steps given to the player, instead of the one of the difficulty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps: Option<usize>,
+    /// what the minotaur does, instead of the behavior of the difficulty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minotaur_behavior: Option<MinotaurBehavior>,
+    /// one string per row of the maze, '#' is a wall and '?' a blank.
+    pub rows: Vec<String>,
+    pub start: (usize, usize),
+    pub exits: Vec<Exit>,
+    #[serde(default)]
+    pub power_ups: Vec<PlacedPowerUp>,
+    #[serde(default)]
+    pub minotaur: Option<(usize, usize)>,
+}
+
+/// get the maze of the game, the one of the maze file if there is one or a generated one.
+pub fn build_maze(settings: &Settings, matcher: &Matcher) -> Result<Maze> {
+    match &settings.load_maze {
+        Some(path) => {
+            let level: Level = Level::load(path)?;
+            level
+                .check_alphabet(&settings.alphabet)
+                .and_then(|_| level.to_maze())
+                .wrap_err_with(|| format!("Invalid maze file: {:?}", path))
+        }
+        None => Ok(Maze::new(settings, matcher)?),
+    }
+}
+
+impl From<&Maze> for Level {
+    fn from(maze: &Maze) -> Self {
+        let rows: Vec<String> = maze
+            .cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        cell if cell.wall => WALL,
+                        cell if cell.blank => BLANK,
+                        cell => cell.value,
+                    })
+                    .collect()
+            })
+            .collect();
+        let power_ups: Vec<PlacedPowerUp> = maze
+            .cells
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.iter().enumerate().filter_map(move |(j, cell)| {
+                    cell.power_up.map(|power| PlacedPowerUp {
+                        location: (i, j),
+                        power,
+                    })
+                })
+            })
+            .collect();
+        Self {
+            version: LEVEL_VERSION,
+            name: None,
+            steps: None,
+            minotaur_behavior: None,
+            rows,
+            start: maze.player_location,
+            exits: maze.exits.clone(),
+            power_ups,
+            minotaur: maze.minotaur,
+        }
+    }
+}
+
+impl Level {
+    /// reads a level from a json file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents: String = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read file: {:?}", path))?;
+        serde_json::from_str(&contents).wrap_err_with(|| format!("Invalid maze file: {:?}", path))
+    }
+
+    /// writes the level to a json file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents: String = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents).wrap_err_with(|| format!("Failed to write file: {:?}", path))
+    }
+
+    /// checks that the letters of the level belong to the alphabet of the dictionary.
+    /// only the mazes loaded for a single game are checked: the levels of the campaign and
+    /// of the editor are drawn by hand for every language, a letter missing from the
+    /// dictionary just never starts a word.
+    pub fn check_alphabet(&self, alphabet: &[char]) -> Result<(), LevelError> {
+        let alphabet: BTreeSet<char> = alphabet.iter().copied().collect();
+        for (i, row) in self.rows.iter().enumerate() {
+            for (j, c) in row.chars().enumerate() {
+                if c != WALL && c != BLANK && !alphabet.contains(&c) {
+                    return Err(LevelError::NotInAlphabet {
+                        letter: c,
+                        location: (i, j),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// builds the maze of the level and checks that it can be played.
+    ///
+    /// # Returns
+    ///
+    /// an error explaining the first problem found in the level.
+    pub fn to_maze(&self) -> Result<Maze, LevelError> {
+        if self.version != LEVEL_VERSION {
+            return Err(LevelError::UnsupportedVersion(self.version));
+        }
+        if self.steps == Some(0) {
+            return Err(LevelError::NoSteps);
+        }
+        let grid: Vec<Vec<char>> = self.rows.iter().map(|row| row.chars().collect()).collect();
+        let (n, m): (usize, usize) = (grid.len(), grid.first().map_or(0, Vec::len));
+        if n == 0 || m == 0 {
+            return Err(LevelError::Empty);
+        }
+        if let Some(i) = grid.iter().position(|row| row.len() != m) {
+            return Err(LevelError::RaggedRow {
+                row: i,
+                cells: grid[i].len(),
+                expected: m,
+            });
+        }
+        for (i, row) in grid.iter().enumerate() {
+            for (j, &c) in row.iter().enumerate() {
+                if c != WALL && c != BLANK && !c.is_alphabetic() {
+                    return Err(LevelError::NotALetter {
+                        letter: c,
+                        location: (i, j),
+                    });
+                }
+            }
+        }
+
+        let maze: Maze = self.to_unchecked_maze();
+        let inside = |(i, j): (usize, usize)| i < n && j < m;
+        if !inside(self.start) || maze.cells[self.start.0][self.start.1].wall {
+            return Err(LevelError::StartNotOpen(self.start));
+        }
+        if self.exits.is_empty() {
+            return Err(LevelError::NoExit);
+        }
+        let mut exits: BTreeSet<(usize, usize)> = BTreeSet::new();
+        for exit in self.exits.iter() {
+            if !inside(exit.location) || exit.location == self.start {
+                return Err(LevelError::ExitOutside(exit.location));
+            }
+            let cell: &MazeCell = &maze.cells[exit.location.0][exit.location.1];
+            if cell.wall || cell.blank {
+                return Err(LevelError::ExitNotOnLetter(exit.location));
+            }
+            if !exits.insert(exit.location) {
+                return Err(LevelError::DuplicateExit(exit.location));
+            }
+        }
+        for placed in self.power_ups.iter() {
+            if !inside(placed.location) || maze.cells[placed.location.0][placed.location.1].wall {
+                return Err(LevelError::PowerUpNotOpen(placed.location));
+            }
+        }
+        if let Some(minotaur) = self.minotaur {
+            if !inside(minotaur) || maze.cells[minotaur.0][minotaur.1].wall {
+                return Err(LevelError::MinotaurNotOpen(minotaur));
+            }
+        }
+        for (i, exit) in self.exits.iter().enumerate() {
+            if maze.shortest_route(i).is_none() {
+                return Err(LevelError::UnreachableExit(exit.location));
+            }
+        }
+        Ok(maze)
+    }
+
+    /// builds the maze of the level without checking that it can be played, so a broken
+    /// level can still be opened in the editor. the short rows are filled with walls, like
+    /// the characters that aren't letters, and what lies outside the maze is dropped.
+    pub fn to_unchecked_maze(&self) -> Maze {
+        let n: usize = self.rows.len().max(1);
+        let m: usize = self
+            .rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        let inside = |(i, j): (usize, usize)| i < n && j < m;
+        let mut cells: Vec<Vec<MazeCell>> = vec![vec![MazeCell::wall(); m]; n];
+        for (i, row) in self.rows.iter().enumerate() {
+            for (j, c) in row.chars().enumerate() {
+                cells[i][j] = match c {
+                    BLANK => MazeCell {
+                        blank: true,
+                        ..Default::default()
+                    },
+                    c if c != WALL && c.is_alphabetic() => MazeCell {
+                        value: c,
+                        ..Default::default()
+                    },
+                    _ => MazeCell::wall(),
+                };
+            }
+        }
+        let exits: Vec<Exit> = self
+            .exits
+            .iter()
+            .filter(|exit| inside(exit.location))
+            .copied()
+            .collect();
+        for exit in exits.iter() {
+            cells[exit.location.0][exit.location.1].exit = Some(exit.kind);
+        }
+        for placed in self
+            .power_ups
+            .iter()
+            .filter(|placed| inside(placed.location))
+        {
+            cells[placed.location.0][placed.location.1].power_up = Some(placed.power);
+        }
+        let start: (usize, usize) = match inside(self.start) {
+            true => self.start,
+            false => (0, 0),
+        };
+        cells[start.0][start.1].visited = true;
+        Maze {
+            cells,
+            exits,
+            player_location: start,
+            minotaur: self.minotaur.filter(|&minotaur| inside(minotaur)),
+            height: n,
+            width: m,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn level(json: &str) -> Level {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn error(level: &Level) -> String {
+        level.to_maze().err().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let loaded: Level = level(
+            r#"{
+                "version": 1,
+                "rows": ["ab#", "c?a", "bca"],
+                "start": [0, 0],
+                "exits": [{"location": [2, 2], "kind": "golden"}],
+                "power_ups": [{"location": [1, 0], "power": "ariadne-thread"}],
+                "minotaur": [2, 0]
+            }"#,
+        );
+        let maze: Maze = loaded.to_maze().unwrap();
+        assert!(maze.cells[0][2].wall);
+        assert!(maze.cells[1][1].blank);
+        assert!(maze.cells[0][0].visited);
+        assert_eq!(maze.cells[1][0].power_up, Some(PowerUP::AriadneThread));
+        assert_eq!(Level::from(&maze), loaded);
+    }
+
+    #[test]
+    fn test_validation() {
+        let valid: Level = level(
+            r#"{"version": 1, "rows": ["ab", "ca"], "start": [0, 0],
+                "exits": [{"location": [1, 1], "kind": "common"}]}"#,
+        );
+        assert!(valid.to_maze().is_ok());
+        assert!(valid.check_alphabet(&['a', 'b', 'c']).is_ok());
+
+        let mut ragged: Level = valid.clone();
+        ragged.rows[1] = "c".to_string();
+        assert_eq!(error(&ragged), "Row 1 has 1 cells instead of 2.");
+
+        let mut foreign: Level = valid.clone();
+        foreign.rows[1] = "cz".to_string();
+        assert!(foreign.to_maze().is_ok());
+        assert_eq!(
+            foreign
+                .check_alphabet(&['a', 'b', 'c'])
+                .err()
+                .unwrap()
+                .to_string(),
+            "The letter 'z' at (1, 1) is not in the alphabet."
+        );
+
+        let mut digit: Level = valid.clone();
+        digit.rows[1] = "c1".to_string();
+        assert_eq!(
+            error(&digit),
+            "The character '1' at (1, 1) is not a letter."
+        );
+
+        let mut outside: Level = valid.clone();
+        outside.start = (2, 0);
+        assert_eq!(
+            error(&outside),
+            "The start (2, 0) is not an open cell of the maze."
+        );
+
+        let mut walled: Level = valid.clone();
+        walled.rows = ["a#", "##", "#a"].iter().map(|&s| s.to_string()).collect();
+        walled.exits[0].location = (2, 1);
+        assert_eq!(
+            error(&walled),
+            "The exit (2, 1) can't be reached from the start."
+        );
+
+        let mut stepless: Level = valid.clone();
+        stepless.steps = Some(0);
+        assert_eq!(stepless.to_maze().err(), Some(LevelError::NoSteps));
+
+        let mut stacked: Level = valid.clone();
+        stacked.exits.push(stacked.exits[0]);
+        assert_eq!(
+            stacked.to_maze().err(),
+            Some(LevelError::DuplicateExit((1, 1)))
+        );
+    }
+}
@@ -1,19 +1,29 @@
 use app::App;
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Command};
 use color_eyre::Result;
 use settings::Settings;
+use word_blazer::matcher;
 
 mod action;
+mod analysis;
 mod app;
+mod cache;
+mod campaign;
 mod cli;
 mod components;
 mod config;
+mod dictionary;
 mod errors;
+mod level;
 mod logging;
-mod matcher;
 mod maze;
+mod render;
+mod scoring;
 mod settings;
+#[cfg(test)]
+mod test_utils;
+mod theme;
 mod tui;
 
 #[tokio::main]
@@ -21,8 +31,29 @@ async fn main() -> Result<()> {
     crate::errors::init()?;
     crate::logging::init()?;
 
-    let args = Cli::parse();
-    let mut app = App::new(Settings::build(args))?;
+    let mut args = Cli::parse();
+    let command: Option<Command> = args.command.take();
+    let dump_automaton: Option<usize> = args.dump_automaton;
+    let settings: Settings = Settings::build(args)?;
+    for summary in settings.rejections.iter() {
+        eprintln!("{}", summary);
+    }
+    match command {
+        Some(Command::Render(render)) => return render::run(&settings, &render),
+        Some(Command::Analyze(analyze)) => return analysis::run(&settings, &analyze),
+        None => {}
+    }
+    if let Some(depth) = dump_automaton {
+        let matcher = cache::load_matcher(&settings.words, &settings.weights);
+        print!("{}", matcher.to_dot(&settings.words, depth));
+        return Ok(());
+    }
+    // the game takes over the terminal, the summaries have to be read before.
+    if !settings.rejections.is_empty() {
+        eprintln!("Press <enter> to start the game.");
+        std::io::stdin().read_line(&mut String::new())?;
+    }
+    let mut app = App::new(settings)?;
     app.run().await?;
     Ok(())
 }
@@ -1,24 +1,232 @@
-use std::collections::{BTreeMap, VecDeque};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, VecDeque},
+    io::{Error, ErrorKind, Result},
+    marker::PhantomData,
+    mem::align_of,
+    ops::Deref,
+    slice,
+    sync::Arc,
+};
 
+/// the magic bytes at the start of a serialized matcher.
+const MAGIC: &[u8; 4] = b"WBAC";
+/// the version of the binary format of the matcher.
+const FORMAT_VERSION: u32 = 2;
+/// the number of values in the header of a serialized matcher.
+const HEADER_LEN: usize = 7;
+/// the number of possible states kept after reading blanks.
+const MAX_STATES: usize = 16;
+
+/// the bytes of a serialized matcher, usually a memory-mapped file.
+pub type Buffer = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// the values that can be read in place from a serialized matcher.
+trait Plain: Copy {
+    /// the number of bytes of a value.
+    const SIZE: usize;
+
+    /// decodes a value from its little-endian bytes.
+    fn decode(bytes: &[u8]) -> Self;
+}
+
+impl Plain for u32 {
+    const SIZE: usize = 4;
+
+    fn decode(bytes: &[u8]) -> Self {
+        u32::from_le_bytes(bytes.try_into().unwrap_or_default())
+    }
+}
+
+impl Plain for f64 {
+    const SIZE: usize = 8;
+
+    fn decode(bytes: &[u8]) -> Self {
+        f64::from_le_bytes(bytes.try_into().unwrap_or_default())
+    }
+}
+
+impl Plain for char {
+    const SIZE: usize = 4;
+
+    fn decode(bytes: &[u8]) -> Self {
+        char::from_u32(u32::decode(bytes)).unwrap_or_default()
+    }
+}
+
+/// an array of the matcher, either built in memory or read in place from a buffer.
+#[derive(Clone)]
+enum Array<T> {
+    Owned(Vec<T>),
+    Shared {
+        buffer: Buffer,
+        offset: usize,
+        len: usize,
+        marker: PhantomData<T>,
+    },
+}
+
+impl<T> Default for Array<T> {
+    fn default() -> Self {
+        Array::Owned(vec![])
+    }
+}
+
+impl<T> From<Vec<T>> for Array<T> {
+    fn from(values: Vec<T>) -> Self {
+        Array::Owned(values)
+    }
+}
+
+impl<T: Plain> Array<T> {
+    /// reads the values starting at a byte offset of a buffer. they are used in place when
+    /// they are aligned and the platform is little-endian, and copied otherwise.
+    /// the caller checks that the bytes are in the buffer and hold valid values.
+    fn read(buffer: &Buffer, offset: usize, len: usize) -> Self {
+        let bytes: &[u8] = &(**buffer).as_ref()[offset..offset + len * T::SIZE];
+        if cfg!(target_endian = "little") && bytes.as_ptr().align_offset(align_of::<T>()) == 0 {
+            Array::Shared {
+                buffer: buffer.clone(),
+                offset,
+                len,
+                marker: PhantomData,
+            }
+        } else {
+            Array::Owned(bytes.chunks_exact(T::SIZE).map(T::decode).collect())
+        }
+    }
+}
+
+impl<T: Plain> Deref for Array<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Array::Owned(values) => values,
+            // SAFETY: `read` checked that the values are aligned, in the buffer and stored in
+            // the byte order of the platform, and the buffer is never modified.
+            Array::Shared {
+                buffer,
+                offset,
+                len,
+                ..
+            } => unsafe {
+                slice::from_raw_parts((**buffer).as_ref().as_ptr().add(*offset).cast(), *len)
+            },
+        }
+    }
+}
+
+/// a node of the trie used while building the matcher.
 #[derive(Default)]
 struct TrieNode {
     pub children: BTreeMap<char, usize>,
     fallback_node: usize,
     output: Vec<usize>,
+    /// the total weight of the words going through this node.
+    weight: f64,
 }
 
-#[derive(Default)]
+/// how the matches found in a text are reported.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// every occurrence of every word, ordered by their end then by length.
+    #[default]
+    Overlapping,
+    /// the matches don't overlap, the one starting first wins and ties go to the longest.
+    LeftmostLongest,
+}
+
+/// the options used when searching a text with `Matcher::find_iter_with`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchOptions {
+    kind: MatchKind,
+    case_insensitive: bool,
+    whole_words: bool,
+}
+
+impl SearchOptions {
+    pub fn kind(mut self, kind: MatchKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// lowercases the text before matching it, the words are expected to be lowercase.
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// only reports the matches that aren't surrounded by letters or digits.
+    pub fn whole_words(mut self, whole_words: bool) -> Self {
+        self.whole_words = whole_words;
+        self
+    }
+}
+
+/// an occurrence of a word in a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// the byte offset of the first character of the occurrence.
+    pub start: usize,
+    /// the byte offset right after the last character of the occurrence.
+    pub end: usize,
+    /// the id of the word, its index in the list the matcher was built with.
+    pub word: usize,
+}
+
+/// an aho-corasick automaton stored in flat arrays.
+/// the edges of node `i` are `labels[edges[i]..edges[i + 1]]` sorted by label,
+/// and its output words are `outputs[output_start[i]..output_start[i + 1]]`.
+/// the transition of state `s` with the letter at index `a` of the alphabet is
+/// `table[s * alphabet.len() + a]`.
+/// the arrays are read in place when the matcher is loaded from a memory-mapped cache.
+#[derive(Default, Clone)]
 pub struct Matcher {
-    nodes: Vec<TrieNode>,
-    current: usize,
+    edges: Array<u32>,
+    labels: Array<char>,
+    targets: Array<u32>,
+    fallback: Array<u32>,
+    output_start: Array<u32>,
+    outputs: Array<u32>,
+    weights: Array<f64>,
+    alphabet: Array<char>,
+    ascii_columns: Vec<u32>,
+    table: Array<u32>,
+    /// the number of characters read to reach each state.
+    depths: Array<u32>,
+    /// the number of characters of each word.
+    lengths: Array<u32>,
+    /// the state each state was reached from.
+    parents: Array<u32>,
+    /// the letter of the edge each state was reached through.
+    parent_labels: Array<char>,
+    /// the number of words starting with the prefix of each state.
+    reachable: Array<u32>,
+    /// the states the automaton could be in, the most promising first.
+    states: Vec<usize>,
+    /// a buffer reused to compute the next states when there are several of them.
+    scratch: Vec<usize>,
+    weighted: bool,
 }
 
 impl Matcher {
     pub fn new(words: Vec<String>) -> Self {
+        Self::with_weights(words, &[])
+    }
+
+    /// builds the matcher where each word has a weight, missing weights are one.
+    /// empty words keep their id but are never matched.
+    pub fn with_weights(words: Vec<String>, weights: &[f64]) -> Self {
         let mut nodes: Vec<TrieNode> = vec![];
         nodes.push(TrieNode::default());
 
         for (i, word) in words.iter().enumerate() {
+            // an empty word would be found before every character, it's never matched.
+            if word.is_empty() {
+                continue;
+            }
+            let weight: f64 = weights.get(i).copied().unwrap_or(1.0);
             let mut current_node: usize = 0;
             for c in word.chars() {
                 current_node = if let Some(next) = nodes[current_node].children.get(&c) {
@@ -28,7 +236,8 @@ impl Matcher {
                     nodes[current_node].children.insert(c, n);
                     nodes.push(TrieNode::default());
                     n
-                }
+                };
+                nodes[current_node].weight += weight;
             }
             nodes[current_node].output.push(i);
         }
@@ -57,37 +266,679 @@ impl Matcher {
             }
         }
 
-        Self { nodes, current: 0 }
+        // finally we flatten the trie.
+        let (mut edges, mut labels, mut targets): (Vec<u32>, Vec<char>, Vec<u32>) =
+            (vec![], vec![], vec![]);
+        let (mut fallback, mut output_start, mut outputs): (Vec<u32>, Vec<u32>, Vec<u32>) =
+            (vec![], vec![], vec![]);
+        let mut node_weights: Vec<f64> = vec![];
+        for node in nodes {
+            edges.push(labels.len() as u32);
+            output_start.push(outputs.len() as u32);
+            for (c, child) in node.children {
+                labels.push(c);
+                targets.push(child as u32);
+            }
+            outputs.extend(node.output.into_iter().map(|word| word as u32));
+            fallback.push(node.fallback_node as u32);
+            node_weights.push(node.weight);
+        }
+        edges.push(labels.len() as u32);
+        output_start.push(outputs.len() as u32);
+        let mut matcher: Matcher = Matcher {
+            edges: edges.into(),
+            labels: labels.into(),
+            targets: targets.into(),
+            fallback: fallback.into(),
+            output_start: output_start.into(),
+            outputs: outputs.into(),
+            weights: node_weights.into(),
+            weighted: !weights.is_empty(),
+            ..Default::default()
+        };
+        matcher.build_tables();
+        matcher
+    }
+
+    /// precomputes the transitions of every state for every letter of the alphabet,
+    /// the depth, parent and reachable words of the states and the length of the words.
+    fn build_tables(&mut self) {
+        let mut alphabet: Vec<char> = self.labels.to_vec();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        self.alphabet = alphabet.into();
+        self.index_alphabet();
+        let width: usize = self.alphabet.len();
+        let mut table: Vec<u32> = vec![0; self.nb_states() * width];
+        let mut depths: Vec<u32> = vec![0; self.nb_states()];
+        let mut parents: Vec<u32> = vec![0; self.nb_states()];
+        let mut parent_labels: Vec<char> = vec![char::default(); self.nb_states()];
+        let mut order: Vec<usize> = vec![];
+
+        // the states are filled in bfs order so the row of the fallback is always ready.
+        let mut queue: VecDeque<usize> = VecDeque::from([0]);
+        while let Some(state) = queue.pop_front() {
+            order.push(state);
+            if state != 0 {
+                let fallback: usize = self.fallback[state] as usize;
+                table.copy_within(fallback * width..(fallback + 1) * width, state * width);
+            }
+            for edge in self.edges[state] as usize..self.edges[state + 1] as usize {
+                let column: usize = self.column(self.labels[edge]).unwrap_or_default();
+                let target: usize = self.targets[edge] as usize;
+                table[state * width + column] = target as u32;
+                depths[target] = depths[state] + 1;
+                parents[target] = state as u32;
+                parent_labels[target] = self.labels[edge];
+                queue.push_back(target);
+            }
+        }
+
+        // a word is in the output of the state where it ends and of the deeper states
+        // reaching it through their fallback links.
+        let nb_words: usize = self
+            .outputs
+            .iter()
+            .max()
+            .map_or(0, |&word| word as usize + 1);
+        // the empty words are never output, their length stays 0.
+        let mut lengths: Vec<u32> = vec![0; nb_words];
+        for (state, &depth) in depths.iter().enumerate() {
+            for &word in self.output(state) {
+                let length: &mut u32 = &mut lengths[word as usize];
+                if *length == 0 || depth < *length {
+                    *length = depth;
+                }
+            }
+        }
+
+        // the words of a state are the ones ending on it and the ones of its children.
+        let mut reachable: Vec<u32> = (0..self.nb_states())
+            .map(|state| {
+                self.words_at(state)
+                    .filter(|&word| lengths[word] == depths[state])
+                    .count() as u32
+            })
+            .collect();
+        for &state in order.iter().skip(1).rev() {
+            reachable[parents[state] as usize] += reachable[state];
+        }
+        self.table = table.into();
+        self.depths = depths.into();
+        self.lengths = lengths.into();
+        self.parents = parents.into();
+        self.parent_labels = parent_labels.into();
+        self.reachable = reachable.into();
+        self.reset();
+    }
+
+    /// maps the ascii letters of the alphabet to their column in the transition table.
+    fn index_alphabet(&mut self) {
+        self.ascii_columns = vec![u32::MAX; 128];
+        for (column, &c) in self.alphabet.iter().enumerate() {
+            if c.is_ascii() {
+                self.ascii_columns[c as usize] = column as u32;
+            }
+        }
+    }
+
+    /// get the column of a letter in the transition table.
+    fn column(&self, c: char) -> Option<usize> {
+        if c.is_ascii() {
+            return self
+                .ascii_columns
+                .get(c as usize)
+                .filter(|&&column| column != u32::MAX)
+                .map(|&column| column as usize);
+        }
+        self.alphabet.binary_search(&c).ok()
+    }
+
+    /// get the number of states of the automaton.
+    pub fn nb_states(&self) -> usize {
+        self.fallback.len()
+    }
+
+    /// get the state reached from a state by following the edge of a character.
+    #[cfg(test)]
+    fn child(&self, state: usize, c: char) -> Option<usize> {
+        let (start, end) = (self.edges[state] as usize, self.edges[state + 1] as usize);
+        let labels: &[char] = &self.labels[start..end];
+        labels
+            .binary_search(&c)
+            .ok()
+            .map(|i| self.targets[start + i] as usize)
+    }
+
+    /// get the words recognized when reaching a state.
+    fn output(&self, state: usize) -> &[u32] {
+        &self.outputs[self.output_start[state] as usize..self.output_start[state + 1] as usize]
+    }
+
+    /// feeds a character to the automaton.
+    ///
+    /// # Returns
+    ///
+    /// the ids of the words ending on this character.
+    pub fn next(&mut self, c: &char) -> impl Iterator<Item = usize> + '_ {
+        // without blanks there is a single state, it's advanced in place.
+        if let [state] = self.states[..] {
+            self.states[0] = self.next_state(state, *c);
+        } else {
+            let mut states: Vec<usize> = std::mem::take(&mut self.scratch);
+            states.clear();
+            states.extend(self.states.iter().map(|&state| self.next_state(state, *c)));
+            self.advance(states);
+        }
+        self.words_at(self.states[0])
+    }
+
+    /// feeds a blank to the automaton, it can stand for any letter so every continuation
+    /// is kept until one of them completes a word.
+    ///
+    /// # Returns
+    ///
+    /// the ids of the words ending on the blank.
+    pub fn next_blank(&mut self) -> impl Iterator<Item = usize> + '_ {
+        let mut states: Vec<usize> = std::mem::take(&mut self.scratch);
+        states.clear();
+        states.extend(
+            self.states
+                .iter()
+                .flat_map(|&state| self.blank_states(state)),
+        );
+        self.advance(states);
+        self.words_at(self.states[0])
     }
 
-    pub fn next(&mut self, c: &char) -> Vec<usize> {
-        if let Some(&next_node) = self.nodes[self.current].children.get(c) {
-            self.current = next_node;
-            return self.nodes[next_node].output.clone();
+    /// get the states reached from a state after reading a blank, one for each letter.
+    pub fn blank_states(&self, state: usize) -> impl Iterator<Item = usize> + '_ {
+        let width: usize = self.alphabet.len();
+        self.table[state * width..(state + 1) * width]
+            .iter()
+            .map(|&state| state as usize)
+    }
+
+    /// get the ids of the words recognized when reaching a state, the longest first.
+    pub fn words_at(&self, state: usize) -> impl Iterator<Item = usize> + '_ {
+        self.output(state).iter().map(|&word| word as usize)
+    }
+
+    /// keeps the most promising states, the deepest ones then the ones leading to the
+    /// heaviest words, and collapses them to the best one completing a word.
+    /// the previous states are kept as the buffer of the next call.
+    fn advance(&mut self, mut states: Vec<usize>) {
+        states.sort_unstable_by(|&a, &b| {
+            self.depths[b]
+                .cmp(&self.depths[a])
+                .then(self.weights[b].total_cmp(&self.weights[a]))
+                .then(a.cmp(&b))
+        });
+        states.dedup();
+        states.truncate(MAX_STATES);
+        if let Some(ind) = states
+            .iter()
+            .position(|&state| !self.output(state).is_empty())
+        {
+            states.swap(0, ind);
+            states.truncate(1);
         }
-        if self.current == 0 {
-            return vec![];
+        if states.is_empty() {
+            states.push(0);
+        }
+        self.scratch = std::mem::replace(&mut self.states, states);
+    }
+
+    /// get the states the automaton could be in, the most promising first.
+    pub fn states(&self) -> &[usize] {
+        &self.states
+    }
+
+    /// get the most promising state the automaton could be in.
+    pub fn current(&self) -> usize {
+        self.states[0]
+    }
+
+    /// get the number of characters read to reach a state.
+    pub fn depth(&self, state: usize) -> usize {
+        self.depths[state] as usize
+    }
+
+    /// get the characters read to reach a state.
+    pub fn prefix(&self, mut state: usize) -> String {
+        let mut letters: Vec<char> = vec![];
+        while state != 0 {
+            letters.push(self.parent_labels[state]);
+            state = self.parents[state] as usize;
         }
-        self.current = self.nodes[self.current].fallback_node;
-        self.next(c)
+        letters.into_iter().rev().collect()
+    }
+
+    /// get the number of words starting with the prefix of a state.
+    pub fn reachable_words(&self, state: usize) -> usize {
+        self.reachable[state] as usize
     }
 
     pub fn options(&self, state: usize) -> Vec<char> {
-        self.nodes[state].children.keys().cloned().collect()
+        self.labels[self.edges[state] as usize..self.edges[state + 1] as usize].to_vec()
+    }
+
+    /// get the possible next characters with the weight of the words they lead to.
+    pub fn weighted_options(&self, state: usize) -> Vec<(char, f64)> {
+        (self.edges[state] as usize..self.edges[state + 1] as usize)
+            .map(|i| (self.labels[i], self.weights[self.targets[i] as usize]))
+            .collect()
     }
 
+    /// checks if the words were given weights.
+    pub fn is_weighted(&self) -> bool {
+        self.weighted
+    }
+
+    /// get the state reached from a state after reading a character, using the transition table.
     pub fn next_state(&self, state: usize, c: char) -> usize {
-        if let Some(&next_state) = self.nodes[state].children.get(&c) {
+        match self.column(c) {
+            Some(column) => self.table[state * self.alphabet.len() + column] as usize,
+            None => 0,
+        }
+    }
+
+    /// get the state reached from a state after reading a character, following the fallback
+    /// links instead of using the transition table.
+    #[cfg(test)]
+    fn walk_state(&self, state: usize, c: char) -> usize {
+        if let Some(next_state) = self.child(state, c) {
             return next_state;
         }
         if state == 0 {
             return 0;
         }
-        self.next_state(self.nodes[state].fallback_node, c)
+        self.walk_state(self.fallback[state] as usize, c)
     }
 
     pub fn reset(&mut self) {
-        self.current = 0;
+        self.states.clear();
+        self.states.push(0);
+    }
+
+    /// get the number of characters of a word.
+    pub fn word_len(&self, word: usize) -> usize {
+        self.lengths.get(word).map_or(0, |&length| length as usize)
+    }
+
+    /// finds the overlapping occurrences of the words in a text.
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> FindIter<'a> {
+        self.find_iter_with(text, SearchOptions::default())
+    }
+
+    /// finds the occurrences of the words in a text.
+    /// the search has its own state, it doesn't change the state used by `next`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - the text to search.
+    /// * `options` - the kind of matches to report and how to compare the characters.
+    pub fn find_iter_with<'a>(&'a self, text: &'a str, options: SearchOptions) -> FindIter<'a> {
+        FindIter {
+            matcher: self,
+            text,
+            options,
+            max_length: self
+                .lengths
+                .iter()
+                .filter(|&&length| length > 0)
+                .max()
+                .map_or(0, |&length| length as usize),
+            position: 0,
+            state: 0,
+            starts: VecDeque::new(),
+            pending: VecDeque::new(),
+            candidate: None,
+        }
+    }
+
+    /// exports the states up to a depth to the graphviz dot format, with the goto edges,
+    /// the fallback links that don't go back to the root and the output words.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - the words the matcher was built with, used to label the outputs.
+    /// * `max_depth` - the states reached after reading more characters are left out.
+    pub fn to_dot(&self, words: &[String], max_depth: usize) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut dot: String =
+            String::from("digraph matcher {\n    rankdir=LR;\n    node [shape=circle];\n");
+        let mut prefixes: Vec<String> = vec![String::new(); self.nb_states()];
+        let mut queue: VecDeque<usize> = VecDeque::from([0]);
+        while let Some(state) = queue.pop_front() {
+            let found: Vec<&str> = self
+                .output(state)
+                .iter()
+                .filter_map(|&word| words.get(word as usize))
+                .map(String::as_str)
+                .collect();
+            let name: &str = match state {
+                0 => "ε",
+                _ => &prefixes[state],
+            };
+            if found.is_empty() {
+                dot.push_str(&format!("    {} [label=\"{}\"];\n", state, escape(name)));
+            } else {
+                dot.push_str(&format!(
+                    "    {} [shape=doublecircle, label=\"{}\\n{{{}}}\"];\n",
+                    state,
+                    escape(name),
+                    escape(&found.join(", "))
+                ));
+            }
+            let fallback: usize = self.fallback[state] as usize;
+            if fallback != 0 {
+                dot.push_str(&format!(
+                    "    {} -> {} [style=dashed, color=gray];\n",
+                    state, fallback
+                ));
+            }
+            if self.depths[state] as usize >= max_depth {
+                continue;
+            }
+            for edge in self.edges[state] as usize..self.edges[state + 1] as usize {
+                let (label, target) = (self.labels[edge], self.targets[edge] as usize);
+                prefixes[target] = format!("{}{}", prefixes[state], label);
+                dot.push_str(&format!(
+                    "    {} -> {} [label=\"{}\"];\n",
+                    state,
+                    target,
+                    escape(&label.to_string())
+                ));
+                queue.push_back(target);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// serializes the automaton and its tables in a compact little-endian binary format,
+    /// every array starts on a multiple of its size so it can be read in place.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = MAGIC.to_vec();
+        let header: [u32; HEADER_LEN] = [
+            FORMAT_VERSION,
+            self.weighted as u32,
+            self.nb_states() as u32,
+            self.labels.len() as u32,
+            self.outputs.len() as u32,
+            self.lengths.len() as u32,
+            self.alphabet.len() as u32,
+        ];
+        fn letters(letters: &[char]) -> impl Iterator<Item = u32> + '_ {
+            letters.iter().map(|&c| c as u32)
+        }
+        for value in header
+            .into_iter()
+            .chain(self.edges.iter().copied())
+            .chain(letters(&self.labels))
+            .chain(self.targets.iter().copied())
+            .chain(self.fallback.iter().copied())
+            .chain(self.output_start.iter().copied())
+            .chain(self.outputs.iter().copied())
+            .chain(letters(&self.alphabet))
+            .chain(self.table.iter().copied())
+            .chain(self.depths.iter().copied())
+            .chain(self.lengths.iter().copied())
+            .chain(self.parents.iter().copied())
+            .chain(letters(&self.parent_labels))
+            .chain(self.reachable.iter().copied())
+        {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+        for weight in self.weights.iter() {
+            bytes.extend_from_slice(&weight.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// reads an automaton serialized with `to_bytes`, copying it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_buffer(Arc::new(bytes.to_vec()))
+    }
+
+    /// reads an automaton serialized with `to_bytes` in place, nothing is rebuilt. the
+    /// arrays are checked so a corrupted buffer is rejected instead of making the matcher
+    /// panic or loop.
+    pub fn from_buffer(buffer: Buffer) -> Result<Self> {
+        let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+        let bytes: &[u8] = (*buffer).as_ref();
+        if !bytes.starts_with(MAGIC) {
+            return Err(invalid("not a serialized matcher"));
+        }
+        let header: Vec<usize> = bytes[MAGIC.len()..]
+            .chunks_exact(4)
+            .take(HEADER_LEN)
+            .map(|chunk| u32::decode(chunk) as usize)
+            .collect();
+        if header.len() != HEADER_LEN {
+            return Err(invalid("truncated matcher"));
+        }
+        if header[0] != FORMAT_VERSION as usize {
+            return Err(invalid("unsupported matcher version"));
+        }
+        let [nodes, nb_edges, nb_outputs, nb_words, width] =
+            [header[2], header[3], header[4], header[5], header[6]];
+        let table_len: usize = nodes
+            .checked_mul(width)
+            .ok_or_else(|| invalid("corrupted matcher"))?;
+
+        // the arrays of u32 in the order they are written, followed by the weights.
+        let lengths: [usize; 13] = [
+            nodes + 1,
+            nb_edges,
+            nb_edges,
+            nodes,
+            nodes + 1,
+            nb_outputs,
+            width,
+            table_len,
+            nodes,
+            nb_words,
+            nodes,
+            nodes,
+            nodes,
+        ];
+        let mut offsets: [usize; 13] = [0; 13];
+        let mut offset: usize = MAGIC.len() + 4 * HEADER_LEN;
+        for (ind, &len) in lengths.iter().enumerate() {
+            offsets[ind] = offset;
+            offset = len
+                .checked_mul(4)
+                .and_then(|size| offset.checked_add(size))
+                .ok_or_else(|| invalid("corrupted matcher"))?;
+        }
+        let weights_offset: usize = offset.next_multiple_of(8);
+        if nodes
+            .checked_mul(8)
+            .and_then(|size| weights_offset.checked_add(size))
+            != Some(bytes.len())
+        {
+            return Err(invalid("truncated matcher"));
+        }
+        let section = |ind: usize| Array::<u32>::read(&buffer, offsets[ind], lengths[ind]);
+        let [edges, labels, targets, fallback, output_start, outputs, alphabet, table, depths, word_lengths, parents, parent_labels, reachable] =
+            std::array::from_fn(section);
+
+        if [&labels, &alphabet, &parent_labels]
+            .iter()
+            .any(|letters| letters.iter().any(|&c| char::from_u32(c).is_none()))
+        {
+            return Err(invalid("invalid label"));
+        }
+        let nodes_only = |values: &[u32]| values.iter().all(|&state| (state as usize) < nodes);
+        // the states must form a trie where a child comes after its parent and falls back to
+        // a shallower state, and the transitions can't skip letters.
+        let corrupted: bool = nodes == 0
+            || nb_edges + 1 != nodes
+            || edges[0] != 0
+            || edges[nodes] as usize != nb_edges
+            || edges.windows(2).any(|w| w[0] > w[1])
+            || output_start[0] != 0
+            || output_start[nodes] as usize != nb_outputs
+            || output_start.windows(2).any(|w| w[0] > w[1])
+            || outputs.iter().any(|&word| word as usize >= nb_words)
+            || word_lengths.iter().any(|&length| length as usize >= nodes)
+            || alphabet.windows(2).any(|w| w[0] >= w[1])
+            || !nodes_only(&targets)
+            || !nodes_only(&fallback)
+            || !nodes_only(&parents)
+            || !nodes_only(&table)
+            || depths[0] != 0
+            || (0..nodes).any(|state| {
+                let depth: u32 = depths[state];
+                let (start, end) = (edges[state] as usize, edges[state + 1] as usize);
+                (start..end).any(|edge| {
+                    let target: usize = targets[edge] as usize;
+                    target <= state
+                        || parents[target] as usize != state
+                        || parent_labels[target] != labels[edge]
+                        || depths[target] != depth + 1
+                        || (edge > start && labels[edge - 1] >= labels[edge])
+                }) || (state != 0 && depths[fallback[state] as usize] >= depth)
+                    || table[state * width..(state + 1) * width]
+                        .iter()
+                        .any(|&next| depths[next as usize] > depth + 1)
+                    || outputs[output_start[state] as usize..output_start[state + 1] as usize]
+                        .iter()
+                        .any(|&word| !(1..=depth).contains(&word_lengths[word as usize]))
+            });
+        if corrupted {
+            return Err(invalid("corrupted matcher"));
+        }
+        let letters = |ind: usize| Array::<char>::read(&buffer, offsets[ind], lengths[ind]);
+        let mut matcher: Matcher = Self {
+            edges,
+            labels: letters(1),
+            targets,
+            fallback,
+            output_start,
+            outputs,
+            weights: Array::read(&buffer, weights_offset, nodes),
+            alphabet: letters(6),
+            table,
+            depths,
+            lengths: word_lengths,
+            parents,
+            parent_labels: letters(11),
+            reachable,
+            weighted: header[1] != 0,
+            ..Default::default()
+        };
+        matcher.index_alphabet();
+        matcher.reset();
+        Ok(matcher)
+    }
+}
+
+/// an iterator over the occurrences of the words in a text, in the order they end.
+pub struct FindIter<'a> {
+    matcher: &'a Matcher,
+    text: &'a str,
+    options: SearchOptions,
+    /// the length of the longest word, no need to remember more characters than that.
+    max_length: usize,
+    /// the byte offset of the next character to read.
+    position: usize,
+    state: usize,
+    /// the byte offsets of the last characters fed to the automaton.
+    starts: VecDeque<usize>,
+    /// the matches found but not returned yet.
+    pending: VecDeque<Match>,
+    /// the best leftmost-longest match that could still be beaten.
+    candidate: Option<Match>,
+}
+
+impl FindIter<'_> {
+    /// checks that a match isn't glued to a letter or a digit.
+    fn is_whole_word(&self, found: &Match) -> bool {
+        let before: Option<char> = self.text[..found.start].chars().next_back();
+        let after: Option<char> = self.text[found.end..].chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric()) && !after.is_some_and(|c| c.is_alphanumeric())
+    }
+
+    /// feeds a character of the text to the automaton and records the matches ending on it.
+    fn read(&mut self, c: char) {
+        let start: usize = self.position;
+        self.position += c.len_utf8();
+        let folded: Vec<char> = if self.options.case_insensitive {
+            c.to_lowercase().collect()
+        } else {
+            vec![c]
+        };
+        for c in folded {
+            self.state = self.matcher.next_state(self.state, c);
+            self.starts.push_back(start);
+            if self.starts.len() > self.max_length {
+                self.starts.pop_front();
+            }
+            for &word in self.matcher.output(self.state) {
+                let length: usize = self.matcher.word_len(word as usize);
+                let found: Match = Match {
+                    start: self.starts[self.starts.len() - length],
+                    end: self.position,
+                    word: word as usize,
+                };
+                if self.options.whole_words && !self.is_whole_word(&found) {
+                    continue;
+                }
+                match self.options.kind {
+                    MatchKind::Overlapping => self.pending.push_back(found),
+                    MatchKind::LeftmostLongest => {
+                        if self.candidate.is_none_or(|candidate| {
+                            (found.start, Reverse(found.end))
+                                < (candidate.start, Reverse(candidate.end))
+                        }) {
+                            self.candidate = Some(found);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// returns the leftmost-longest candidate once no later match can start before it,
+    /// the search then resumes right after it.
+    fn settle(&mut self) -> Option<Match> {
+        let candidate: Match = self.candidate?;
+        let depth: usize = self.matcher.depths[self.state] as usize;
+        let earliest: usize = match depth {
+            0 => self.position,
+            _ => self.starts[self.starts.len() - depth],
+        };
+        if earliest <= candidate.start {
+            return None;
+        }
+        self.candidate = None;
+        self.position = candidate.end;
+        self.state = 0;
+        self.starts.clear();
+        Some(candidate)
+    }
+}
+
+impl Iterator for FindIter<'_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(found) = self.pending.pop_front() {
+                return Some(found);
+            }
+            let Some(c) = self.text[self.position..].chars().next() else {
+                return self.candidate.take();
+            };
+            self.read(c);
+            if let Some(found) = self.settle() {
+                return Some(found);
+            }
+        }
     }
 }
 
@@ -144,6 +995,218 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_serialization() {
+        let words: Vec<String> = ["he", "she", "his", "hers", "née"]
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+        let matcher: Matcher = Matcher::with_weights(words, &[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let bytes: Vec<u8> = matcher.to_bytes();
+        let mut loaded: Matcher = Matcher::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.to_bytes(), bytes);
+        assert!(loaded.is_weighted());
+        assert_eq!(
+            loaded.weighted_options(0),
+            vec![('h', 8.0), ('n', 5.0), ('s', 2.0)]
+        );
+        let found: Vec<Vec<usize>> = "shers".chars().map(|c| loaded.next(&c).collect()).collect();
+        assert_eq!(found, vec![vec![], vec![], vec![1, 0], vec![], vec![3]]);
+        assert_eq!(loaded.prefix(loaded.current()), "hers");
+        assert_eq!(loaded.reachable_words(0), 5);
+        assert!(Matcher::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Matcher::from_bytes(b"nope").is_err());
+
+        let corrupt = |section: usize, ind: usize, value: u32| {
+            let mut corrupted: Vec<u8> = bytes.clone();
+            let offset: usize = 4 + 4 * (HEADER_LEN + section + ind);
+            corrupted[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            Matcher::from_bytes(&corrupted).is_err()
+        };
+        let (nodes, nb_edges) = (matcher.nb_states(), matcher.labels.len());
+        // an edge back to the root would make a cycle.
+        assert!(corrupt(nodes + 1 + nb_edges, 0, 0));
+        // an output id past the words would make the lengths grow.
+        assert!(corrupt(
+            3 * nodes + 2 + 2 * nb_edges,
+            0,
+            matcher.lengths.len() as u32
+        ));
+        // a huge number of words can't be announced without its lengths.
+        let mut header: Vec<u8> = bytes.clone();
+        header[4 + 4 * 5..4 + 4 * 6].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(Matcher::from_bytes(&header).is_err());
+    }
+
+    #[test]
+    fn test_transition_table() {
+        let words: Vec<String> = ["abck", "bcdk", "cde", "def", "abkcdefg", "été"]
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+        let matcher: Matcher = Matcher::new(words);
+        for state in 0..matcher.nb_states() {
+            for c in "abcdefgkétz".chars() {
+                assert_eq!(matcher.next_state(state, c), matcher.walk_state(state, c));
+            }
+        }
+    }
+
+    fn spans(matcher: &Matcher, text: &str, options: SearchOptions) -> Vec<(usize, usize, usize)> {
+        matcher
+            .find_iter_with(text, options)
+            .map(|found| (found.start, found.end, found.word))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_overlapping() {
+        let words: Vec<String> = ["he", "she", "his", "hers"]
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+        let matcher: Matcher = Matcher::new(words);
+        assert_eq!(
+            spans(&matcher, "ushers", SearchOptions::default()),
+            vec![(1, 4, 1), (2, 4, 0), (2, 6, 3)]
+        );
+        assert_eq!(matcher.find_iter("").count(), 0);
+        assert_eq!(matcher.word_len(3), 4);
+    }
+
+    #[test]
+    fn test_empty_word() {
+        let words: Vec<String> = ["", "ab"].iter().map(|&s| s.to_string()).collect();
+        let mut matcher: Matcher = Matcher::new(words);
+        assert_eq!(matcher.word_len(0), 0);
+        assert_eq!(matcher.word_len(1), 2);
+        let mut search: FindIter = matcher.find_iter("xyzxyzxyz");
+        assert_eq!(search.by_ref().count(), 0);
+        assert!(search.starts.len() <= 2);
+        for options in [
+            SearchOptions::default(),
+            SearchOptions::default().kind(MatchKind::LeftmostLongest),
+        ] {
+            assert_eq!(spans(&matcher, "xab", options), vec![(1, 3, 1)]);
+        }
+        assert_eq!(matcher.next(&'a').count(), 0);
+        assert_eq!(matcher.next(&'b').collect::<Vec<usize>>(), vec![1]);
+        let loaded: Matcher = Matcher::from_bytes(&matcher.to_bytes()).unwrap();
+        assert_eq!(
+            spans(&loaded, "xab", SearchOptions::default()),
+            vec![(1, 3, 1)]
+        );
+    }
+
+    #[test]
+    fn test_find_leftmost_longest() {
+        let words: Vec<String> = ["bcd", "abcde", "ab", "de", "e"]
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+        let matcher: Matcher = Matcher::new(words);
+        let leftmost: SearchOptions = SearchOptions::default().kind(MatchKind::LeftmostLongest);
+        assert_eq!(spans(&matcher, "abcde", leftmost), vec![(0, 5, 1)]);
+        assert_eq!(
+            spans(&matcher, "abcdxbcde", leftmost),
+            vec![(0, 2, 2), (5, 8, 0), (8, 9, 4)]
+        );
+    }
+
+    #[test]
+    fn test_find_options() {
+        let words: Vec<String> = ["cat", "été", "at"]
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+        let matcher: Matcher = Matcher::new(words);
+        let text: &str = "Cat, l'ÉTÉ, concat";
+        assert_eq!(
+            spans(&matcher, text, SearchOptions::default()),
+            vec![(1, 3, 2), (17, 20, 0), (18, 20, 2)]
+        );
+        let insensitive: SearchOptions = SearchOptions::default().case_insensitive(true);
+        assert_eq!(
+            spans(&matcher, text, insensitive),
+            vec![(0, 3, 0), (1, 3, 2), (7, 12, 1), (17, 20, 0), (18, 20, 2)]
+        );
+        assert_eq!(
+            spans(&matcher, text, insensitive.whole_words(true)),
+            vec![(0, 3, 0), (7, 12, 1)]
+        );
+        assert_eq!(&text[7..12], "ÉTÉ");
+    }
+
+    #[test]
+    fn test_find_does_not_move_the_game_state() {
+        let words: Vec<String> = ["abc"].iter().map(|&s| s.to_string()).collect();
+        let mut matcher: Matcher = Matcher::new(words);
+        assert_eq!(matcher.next(&'a').count(), 0);
+        assert_eq!(matcher.find_iter("xyzabc").count(), 1);
+        assert_eq!(matcher.next(&'b').count(), 0);
+        assert_eq!(matcher.next(&'c').collect::<Vec<usize>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_dot_export() {
+        let words: Vec<String> = ["he", "she"].iter().map(|&s| s.to_string()).collect();
+        let matcher: Matcher = Matcher::new(words.clone());
+        let dot: String = matcher.to_dot(&words, 3);
+        assert!(dot.starts_with("digraph matcher {"));
+        assert!(dot.contains("0 -> 1 [label=\"h\"];"));
+        assert!(dot.contains("5 [shape=doublecircle, label=\"she\\n{she, he}\"];"));
+        assert!(dot.contains("4 -> 1 [style=dashed, color=gray];"));
+        assert!(!matcher.to_dot(&words, 1).contains("label=\"sh\""));
+    }
+
+    #[test]
+    fn test_blanks() {
+        let words: Vec<String> = ["cat", "cow", "dog"]
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+        let mut matcher: Matcher = Matcher::new(words);
+        assert_eq!(matcher.next(&'c').count(), 0);
+        assert_eq!(matcher.next_blank().count(), 0);
+        // "ca", "co", "c", "d" and the root.
+        assert_eq!(matcher.states().len(), 5);
+        assert_eq!(matcher.next(&'t').collect::<Vec<usize>>(), vec![0]);
+        assert_eq!(matcher.states().len(), 1);
+
+        matcher.reset();
+        assert_eq!(matcher.next(&'d').count(), 0);
+        assert_eq!(matcher.next(&'o').count(), 0);
+        assert_eq!(matcher.next_blank().collect::<Vec<usize>>(), vec![2]);
+
+        // a blank following a blank keeps every pair of letters.
+        matcher.reset();
+        assert_eq!(matcher.next_blank().count(), 0);
+        assert_eq!(matcher.next_blank().count(), 0);
+        assert_eq!(matcher.next(&'w').collect::<Vec<usize>>(), vec![1]);
+    }
+
+    #[test]
+    fn test_prefix() {
+        let words: Vec<String> = ["he", "she", "his", "hers", "shell"]
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+        let mut matcher: Matcher = Matcher::new(words);
+        assert_eq!(matcher.reachable_words(0), 5);
+        "ush".chars().for_each(|c| matcher.next(&c).for_each(drop));
+        assert_eq!(matcher.prefix(matcher.current()), "sh");
+        assert_eq!(matcher.depth(matcher.current()), 2);
+        assert_eq!(matcher.reachable_words(matcher.current()), 2);
+        matcher.next(&'e').for_each(drop);
+        assert_eq!(matcher.prefix(matcher.current()), "she");
+        assert_eq!(matcher.reachable_words(matcher.current()), 2);
+        matcher.next(&'r').for_each(drop);
+        assert_eq!(matcher.prefix(matcher.current()), "her");
+        assert_eq!(matcher.reachable_words(matcher.current()), 1);
+        matcher.reset();
+        assert_eq!(matcher.prefix(matcher.current()), "");
+    }
+
     #[test]
     fn test_repetitive_prefixes_matches() {
         let words: Vec<String> = ["a", "aa", "aaa"].iter().map(|&s| s.to_string()).collect();
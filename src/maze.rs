@@ -1,7 +1,18 @@
-use crate::{matcher::Matcher, settings::Settings};
-use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use crate::{
+    errors::GenerationError,
+    matcher::Matcher,
+    settings::{MinotaurBehavior, Placement, Pockets, Settings},
+    theme::Palette,
+};
+use clap::ValueEnum;
+use rand::{distributions::WeightedIndex, rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use ratatui::{prelude::*, widgets::*};
-use std::collections::{BTreeSet, VecDeque};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashSet, VecDeque},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 const DIRECTIONS: [(i32, i32); 8] = [
     (-1, 1),
@@ -13,11 +24,21 @@ const DIRECTIONS: [(i32, i32); 8] = [
     (-1, -1),
     (-1, 0),
 ];
-const ALPHABET: [char; 26] = [
-    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
-    't', 'u', 'v', 'w', 'x', 'y', 'z',
-];
-const POWERUPS: [PowerUP; 5] = [
+/// the cost of a step for Ariadne's thread.
+const STEP_COST: usize = 3;
+/// the cost of a step extending a word started on the previous cells.
+const PREFIX_STEP_COST: usize = 2;
+/// the cost of a step completing a word.
+const WORD_STEP_COST: usize = 0;
+/// the number of (cell, state) pairs explored before the thread stops following the words.
+const THREAD_SEARCH: usize = 200_000;
+/// the number of (cell, state) pairs explored before giving up on finding more words.
+const WORDS_SEARCH: usize = 200_000;
+/// the longest walk suggested by a hint.
+const HINT_LENGTH: usize = 12;
+/// the number of (cell, state) pairs explored before giving up on a hint.
+const HINT_SEARCH: usize = 200_000;
+pub const POWERUPS: [PowerUP; 5] = [
     PowerUP::AriadneThread,
     PowerUP::HeliosTorch,
     // PowerUP::ProteusGift,
@@ -26,7 +47,8 @@ const POWERUPS: [PowerUP; 5] = [
     PowerUP::BifrostBridge,
 ];
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
 pub enum PowerUP {
     AriadneThread,
     HeliosTorch,
@@ -43,12 +65,23 @@ impl PowerUP {
             PowerUP::AriadneThread => "Ariadne's thread : Magical thread that guides you through the maze, as it guided Theseus through the Labyrinth.",
             PowerUP::HeliosTorch => "The torch of helios : Illuminates dark areas with the brilliant light of the sun god's torch.",
             // PowerUP::ProteusGift => "Proteus's Gift : Transforms into the character you need most, channeling Proteus' shapeshifting abilities.",
-            PowerUP::OdinDraupnir => "Draupnir : Multiplies by 8 your score with the power of Odin's self-replicating ring.",
+            PowerUP::OdinDraupnir => "Draupnir : Multiplies the score of your words with the power of Odin's self-replicating ring.",
             PowerUP::ThorMjolnir => "Thor's hammer : Destroys all walls within 3 cells radius, channeling Thor's mighty hammer Mjolnir.",
             PowerUP::BifrostBridge => "The BifrostBridge : Teleports you to a random position in the maze, using the power of the rainbow bridge that connects realms.",
         }
     }
 
+    /// get the name of the power_up, as written in the maze files.
+    pub fn name(&self) -> &str {
+        match self {
+            PowerUP::AriadneThread => "ariadne-thread",
+            PowerUP::HeliosTorch => "helios-torch",
+            PowerUP::OdinDraupnir => "odin-draupnir",
+            PowerUP::ThorMjolnir => "thor-mjolnir",
+            PowerUP::BifrostBridge => "bifrost-bridge",
+        }
+    }
+
     /// get the color associated with the power_up.
     pub fn color(&self) -> Color {
         match self {
@@ -62,6 +95,64 @@ impl PowerUP {
     }
 }
 
+/// the different exits of the maze, each one with its own reward.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExitKind {
+    /// an exit placed closer to the start that gives a small bonus.
+    Common,
+    /// an exit placed far from the start that multiplies the score.
+    Golden,
+}
+
+impl ExitKind {
+    /// get the name of the exit.
+    pub fn name(&self) -> &str {
+        match self {
+            ExitKind::Common => "common",
+            ExitKind::Golden => "golden",
+        }
+    }
+
+    /// get the color associated with the exit.
+    pub fn color(&self) -> Color {
+        match self {
+            ExitKind::Common => Color::Magenta,
+            ExitKind::Golden => Color::Yellow,
+        }
+    }
+
+    /// get the final score after leaving the maze through this exit.
+    pub fn reward(&self, score: usize) -> usize {
+        match self {
+            ExitKind::Common => score.saturating_add(20),
+            ExitKind::Golden => score.saturating_mul(3),
+        }
+    }
+}
+
+/// what Ariadne's thread leads to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ThreadTarget {
+    /// the exit at this index of the exits of the maze.
+    Exit(usize),
+    /// the closest power up.
+    PowerUp,
+}
+
+impl Default for ThreadTarget {
+    fn default() -> Self {
+        ThreadTarget::Exit(0)
+    }
+}
+
+/// an exit of the maze and its location.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Exit {
+    pub location: (usize, usize),
+    pub kind: ExitKind,
+}
+
 /// represents a single cell in the maze.
 #[derive(Default, Clone)]
 pub struct MazeCell {
@@ -69,7 +160,9 @@ pub struct MazeCell {
     pub power_up: Option<PowerUP>,
     pub wall: bool,
     pub visited: bool,
-    pub exit: bool,
+    pub exit: Option<ExitKind>,
+    /// a blank stands for any letter, like in scrabble.
+    pub blank: bool,
 }
 
 impl MazeCell {
@@ -83,10 +176,12 @@ impl MazeCell {
 }
 
 /// represents the entire maze using a matrix of cells and player location.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Maze {
     pub cells: Vec<Vec<MazeCell>>,
+    pub exits: Vec<Exit>,
     pub player_location: (usize, usize),
+    pub minotaur: Option<(usize, usize)>,
     pub height: usize,
     pub width: usize,
 }
@@ -129,6 +224,7 @@ impl Maze {
         mut state: usize,
         word_prob: f64,
         word_builder: &Matcher,
+        alphabet: &[char],
         rng: &mut StdRng,
     ) {
         let mut shuffled_directions: Vec<usize> = (0..8).collect();
@@ -143,12 +239,17 @@ impl Maze {
                     continue;
                 }
                 let next_char: char = if rng.gen::<f64>() < word_prob {
-                    *word_builder
-                        .options(state)
-                        .choose(rng)
-                        .unwrap_or(ALPHABET.choose(rng).unwrap())
+                    let options: Vec<(char, f64)> = word_builder.weighted_options(state);
+                    let option: Option<&(char, f64)> = if word_builder.is_weighted() {
+                        options.choose_weighted(rng, |(_, weight)| *weight).ok()
+                    } else {
+                        options.choose(rng)
+                    };
+                    option
+                        .map(|(c, _)| *c)
+                        .unwrap_or(*alphabet.choose(rng).unwrap())
                 } else {
-                    *ALPHABET.choose(rng).unwrap()
+                    *alphabet.choose(rng).unwrap()
                 };
                 self.cells[next.0][next.1].value = next_char;
                 self.fill_maze_characters(
@@ -156,6 +257,7 @@ impl Maze {
                     word_builder.next_state(state, next_char),
                     word_prob,
                     word_builder,
+                    alphabet,
                     rng,
                 );
             };
@@ -178,26 +280,249 @@ impl Maze {
         }
     }
 
-    /// checks if it's possible to go the end of maze from the player location.
+    /// computes the walking distance from a location to every cell of the maze.
+    ///
+    /// # Returns
+    ///
+    /// a matrix where unreachable cells are None.
+    pub fn distances(&self, from: (usize, usize)) -> Vec<Vec<Option<usize>>> {
+        self.distances_from(&[from])
+    }
+
+    /// computes the walking distance from the closest of several locations to every cell
+    /// of the maze.
+    ///
+    /// # Returns
+    ///
+    /// a matrix where unreachable cells are None.
+    pub fn distances_from(&self, sources: &[(usize, usize)]) -> Vec<Vec<Option<usize>>> {
+        let mut dist: Vec<Vec<Option<usize>>> = vec![vec![None; self.width]; self.height];
+        let mut queue: VecDeque<(usize, usize)> = sources.iter().copied().collect();
+        for &(x, y) in sources {
+            dist[x][y] = Some(0);
+        }
+        while let Some((x, y)) = queue.pop_front() {
+            let d = dist[x][y].unwrap_or_default();
+            for direction in 0..8 {
+                if let Some((next_x, next_y)) = self.valid_coordenates((x, y), direction) {
+                    if dist[next_x][next_y].is_none() {
+                        dist[next_x][next_y] = Some(d + 1);
+                        queue.push_back((next_x, next_y));
+                    }
+                }
+            }
+        }
+        dist
+    }
+
+    /// counts the groups of walkable cells that can't be reached from each other.
+    pub fn components(&self) -> usize {
+        self.label_components().1.len()
+    }
+
+    /// labels every walkable cell with the group of cells it can reach, using flood fill.
+    ///
+    /// # Returns
+    ///
+    /// the label of each cell, None for walls, and the size of each group.
+    fn label_components(&self) -> (Vec<Vec<Option<usize>>>, Vec<usize>) {
+        let mut labels: Vec<Vec<Option<usize>>> = vec![vec![None; self.width]; self.height];
+        let mut sizes: Vec<usize> = vec![];
+        for i in 0..self.height {
+            for j in 0..self.width {
+                if self.cells[i][j].wall || labels[i][j].is_some() {
+                    continue;
+                }
+                let label: usize = sizes.len();
+                sizes.push(1);
+                labels[i][j] = Some(label);
+                let mut stack: Vec<(usize, usize)> = vec![(i, j)];
+                while let Some(cell) = stack.pop() {
+                    for direction in 0..8 {
+                        if let Some((x, y)) = self.valid_coordenates(cell, direction) {
+                            if labels[x][y].is_none() {
+                                labels[x][y] = Some(label);
+                                sizes[label] += 1;
+                                stack.push((x, y));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        (labels, sizes)
+    }
+
+    /// makes every walkable cell reachable from the largest group of cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `pockets` - whether the cut off cells are connected by opening walls or turned into walls.
+    fn repair_pockets(&mut self, pockets: Pockets) {
+        loop {
+            let (labels, sizes) = self.label_components();
+            let Some(largest) = (0..sizes.len()).max_by_key(|&label| sizes[label]) else {
+                return;
+            };
+            if sizes.len() == 1 {
+                return;
+            }
+            match pockets {
+                Pockets::Connect => self.open_walls(&labels, largest),
+                Pockets::Fill => {
+                    for (i, row) in labels.iter().enumerate() {
+                        for (j, label) in row.iter().enumerate() {
+                            if label.is_some_and(|label| label != largest) {
+                                self.cells[i][j].wall = true;
+                            }
+                        }
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    /// opens the walls on the shortest way from a group of cells to another one.
+    ///
+    /// # Arguments
+    ///
+    /// * `labels` - the groups of the cells given by `label_components`.
+    /// * `from` - the label of the group the walls are opened from.
+    fn open_walls(&mut self, labels: &[Vec<Option<usize>>], from: usize) {
+        // a bfs going through the walls, started from every cell of the group.
+        let mut parent: Vec<Vec<Option<(usize, usize)>>> =
+            vec![vec![None; self.width]; self.height];
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+        for (i, row) in labels.iter().enumerate() {
+            for (j, &label) in row.iter().enumerate() {
+                if label == Some(from) {
+                    parent[i][j] = Some((i, j));
+                    queue.push_back((i, j));
+                }
+            }
+        }
+        while let Some((x, y)) = queue.pop_front() {
+            for (di, dj) in DIRECTIONS {
+                let (i, j) = (x as i32 + di, y as i32 + dj);
+                if i < 0 || j < 0 || i as usize >= self.height || j as usize >= self.width {
+                    continue;
+                }
+                let (i, j) = (i as usize, j as usize);
+                if parent[i][j].is_some() {
+                    continue;
+                }
+                parent[i][j] = Some((x, y));
+                if labels[i][j].is_none() {
+                    queue.push_back((i, j));
+                    continue;
+                }
+                // another group was reached, the walls leading to it are opened.
+                let mut cell: (usize, usize) = (x, y);
+                while self.cells[cell.0][cell.1].wall {
+                    self.cells[cell.0][cell.1].wall = false;
+                    cell = parent[cell.0][cell.1].unwrap_or(cell);
+                }
+                return;
+            }
+        }
+    }
+
+    /// finds the words that can be read along a path of neighboring cells that doesn't go
+    /// through the same cell twice. a path can end on an exit but not go through it.
+    /// every cell is explored once per state of the matcher, and the search gives up after
+    /// a budget of (cell, state) pairs so the blanks can't make it explode.
+    ///
+    /// # Arguments
+    ///
+    /// * `matcher` - the automaton of the words.
+    ///
+    /// # Returns
+    ///
+    /// the ids of the words found.
+    pub fn words(&self, matcher: &Matcher) -> BTreeSet<usize> {
+        let mut words: BTreeSet<usize> = BTreeSet::new();
+        let mut on_path: Vec<Vec<bool>> = vec![vec![false; self.width]; self.height];
+        let mut seen: HashSet<((usize, usize), usize)> = HashSet::new();
+        for i in 0..self.height {
+            for j in 0..self.width {
+                if !self.cells[i][j].wall && self.cells[i][j].exit.is_none() {
+                    self.collect_words((i, j), 0, matcher, &mut on_path, &mut seen, &mut words);
+                }
+            }
+        }
+        words
+    }
+
+    /// extends the path with a cell while the letters read are the prefix of a word.
+    fn collect_words(
+        &self,
+        cell: (usize, usize),
+        state: usize,
+        matcher: &Matcher,
+        on_path: &mut Vec<Vec<bool>>,
+        seen: &mut HashSet<((usize, usize), usize)>,
+        words: &mut BTreeSet<usize>,
+    ) {
+        let current: &MazeCell = &self.cells[cell.0][cell.1];
+        let states: Vec<usize> = if current.blank {
+            matcher.blank_states(state).collect()
+        } else {
+            vec![matcher.next_state(state, current.value)]
+        };
+        on_path[cell.0][cell.1] = true;
+        for next_state in states {
+            // a shallower state means a fallback link was taken, no word starts with the path.
+            if matcher.depth(next_state) != matcher.depth(state) + 1
+                || seen.len() > WORDS_SEARCH
+                || !seen.insert((cell, next_state))
+            {
+                continue;
+            }
+            words.extend(matcher.words_at(next_state));
+            if current.exit.is_some() {
+                continue;
+            }
+            for direction in 0..8 {
+                if let Some(next) = self.valid_coordenates(cell, direction) {
+                    if !on_path[next.0][next.1] {
+                        self.collect_words(next, next_state, matcher, on_path, seen, words);
+                    }
+                }
+            }
+        }
+        on_path[cell.0][cell.1] = false;
+    }
+
+    /// checks if it's possible to go to an exit of the maze from the player location.
+    ///
+    /// # Arguments
+    ///
+    /// * `exit` - the index of the targeted exit in `exits`.
     ///
     /// # Returns
     ///
-    /// None if it's not possible to get the end.
-    /// Some(path) the set of coordenates that are on the path from the player to the end.
-    pub fn shortest_route(&self) -> Option<BTreeSet<(i32, i32)>> {
+    /// None if it's not possible to get the exit.
+    /// Some(path) the set of coordenates that are on the path from the player to the exit.
+    pub fn shortest_route(&self, exit: usize) -> Option<BTreeSet<(i32, i32)>> {
+        self.route_to(self.exits.get(exit)?.location)
+    }
+
+    /// checks if it's possible to go to the target from the player location.
+    fn route_to(&self, target: (usize, usize)) -> Option<BTreeSet<(i32, i32)>> {
         // we are using bfs to find the shortest path.
         let mut vis: Vec<Vec<bool>> = vec![vec![false; self.width]; self.height];
         let mut next_direction: Vec<Vec<Option<usize>>> = vec![vec![None; self.width]; self.height];
         let mut stack: VecDeque<(usize, usize)> =
             VecDeque::from([(self.player_location.0, self.player_location.1)]);
-        let mut exit: Option<(usize, usize)> = None;
+        let mut found: Option<(usize, usize)> = None;
         while let Some((x, y)) = stack.pop_front() {
             if vis[x][y] {
                 continue;
             }
             vis[x][y] = true;
-            if self.cells[x][y].exit {
-                exit = Some((x, y));
+            if (x, y) == target {
+                found = Some((x, y));
                 break;
             }
             for d in 0..8 {
@@ -210,7 +535,7 @@ impl Maze {
                 }
             }
         }
-        let (mut x, mut y) = exit?;
+        let (mut x, mut y) = found?;
         let mut ans: BTreeSet<(i32, i32)> = BTreeSet::from([(x as i32, y as i32)]);
         while let Some(d) = next_direction[x][y] {
             (x, y) = self.valid_coordenates((x, y), d)?;
@@ -220,10 +545,213 @@ impl Maze {
         Some(ans)
     }
 
+    /// finds the way Ariadne's thread follows from the player location: a short way that
+    /// goes out of its way to spell words, using dijkstra on the cells and the states the
+    /// matcher would reach on them. the steps extending a word are cheaper and the ones
+    /// completing a word are free. the thread never goes through an exit that isn't its target.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - where the thread leads.
+    /// * `matcher` - the automaton of the words, in the state reached by the player.
+    ///
+    /// # Returns
+    ///
+    /// None if the target can't be reached.
+    /// Some(path) the set of coordenates that are on the thread.
+    pub fn ariadne_thread(
+        &self,
+        target: ThreadTarget,
+        matcher: &Matcher,
+    ) -> Option<BTreeSet<(i32, i32)>> {
+        let is_target = |(x, y): (usize, usize)| match target {
+            ThreadTarget::Exit(exit) => {
+                self.exits.get(exit).map(|exit| exit.location) == Some((x, y))
+            }
+            ThreadTarget::PowerUp => {
+                // the power ups already taken stay on their visited cells.
+                self.cells[x][y].power_up.is_some() && !self.cells[x][y].visited
+            }
+        };
+        // every node is a cell and the state reached on it.
+        type Node = ((usize, usize), usize);
+        let mut cost: BTreeMap<Node, usize> = BTreeMap::new();
+        let mut previous: BTreeMap<Node, Node> = BTreeMap::new();
+        let mut queue: BinaryHeap<Reverse<(usize, Node)>> = BinaryHeap::new();
+        for &state in matcher.states() {
+            cost.insert((self.player_location, state), 0);
+            queue.push(Reverse((0, (self.player_location, state))));
+        }
+        let mut found: Option<Node> = None;
+        while let Some(Reverse((c, (cell, state)))) = queue.pop() {
+            if cost.get(&(cell, state)).is_some_and(|&best| best < c) {
+                continue;
+            }
+            if is_target(cell) {
+                found = Some((cell, state));
+                break;
+            }
+            if self.cells[cell.0][cell.1].exit.is_some() && cell != self.player_location {
+                continue;
+            }
+            for direction in 0..8 {
+                let Some(next) = self.valid_coordenates(cell, direction) else {
+                    continue;
+                };
+                let next_cell: &MazeCell = &self.cells[next.0][next.1];
+                // walking over a visited cell breaks the word, like in the game.
+                let states: Vec<usize> = if next_cell.visited {
+                    vec![0]
+                } else if next_cell.blank {
+                    matcher.blank_states(state).collect()
+                } else {
+                    vec![matcher.next_state(state, next_cell.value)]
+                };
+                for mut next_state in states {
+                    // past the budget the thread stops following the words.
+                    if cost.len() > THREAD_SEARCH {
+                        next_state = 0;
+                    }
+                    let step: usize = if matcher.words_at(next_state).next().is_some() {
+                        WORD_STEP_COST
+                    } else if matcher.depth(next_state) > 1
+                        && matcher.depth(next_state) == matcher.depth(state) + 1
+                    {
+                        PREFIX_STEP_COST
+                    } else {
+                        STEP_COST
+                    };
+                    let node: Node = (next, next_state);
+                    if cost.get(&node).is_none_or(|&best| c + step < best) {
+                        cost.insert(node, c + step);
+                        previous.insert(node, (cell, state));
+                        queue.push(Reverse((c + step, node)));
+                    }
+                }
+            }
+        }
+        let mut node: Node = found?;
+        let mut thread: BTreeSet<(i32, i32)> = BTreeSet::new();
+        while let Some(&before) = previous.get(&node) {
+            thread.insert((node.0 .0 as i32, node.0 .1 as i32));
+            node = before;
+        }
+        Some(thread)
+    }
+
+    /// picks the start of the player with a single bfs from the golden exit. the start is
+    /// taken in a band of distances from the exit: at least a third of the sizes of the maze
+    /// when the exit allows it, and always in the farthest third of the cells it reaches.
+    ///
+    /// # Arguments
+    ///
+    /// * `exit` - the location of the golden exit.
+    /// * `rng` - the rng of the maze.
+    ///
+    /// # Returns
+    ///
+    /// an error if no other cell can be reached from the exit.
+    fn pick_start(
+        &self,
+        exit: (usize, usize),
+        rng: &mut StdRng,
+    ) -> Result<(usize, usize), GenerationError> {
+        let distances: Vec<Vec<Option<usize>>> = self.distances(exit);
+        let farthest: usize = distances
+            .iter()
+            .flatten()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(0);
+        let closest: usize = ((self.height + self.width) / 3 + 1)
+            .min(farthest - farthest / 3)
+            .max(1);
+        let candidates: Vec<(usize, usize)> = (0..self.height)
+            .flat_map(|i| (0..self.width).map(move |j| (i, j)))
+            .filter(|&(i, j)| distances[i][j].is_some_and(|d| (closest..=farthest).contains(&d)))
+            .collect();
+        candidates
+            .choose(rng)
+            .copied()
+            .ok_or(GenerationError::NoStart)
+    }
+
+    /// places the power ups on the free cells, without getting stuck when they are few.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the number, placement and weights of the power ups.
+    /// * `rng` - the rng of the maze.
+    fn place_power_ups(
+        &mut self,
+        settings: &Settings,
+        rng: &mut StdRng,
+    ) -> Result<(), GenerationError> {
+        if settings.nb_power_ups == 0 {
+            return Ok(());
+        }
+        let kinds: WeightedIndex<f64> = WeightedIndex::new(settings.power_up_weights)
+            .map_err(|_| GenerationError::InvalidPowerUpWeights)?;
+        let distances: Vec<Vec<Option<usize>>> = self.distances(self.player_location);
+        let mut candidates: Vec<(usize, usize)> = (0..self.height)
+            .flat_map(|i| (0..self.width).map(move |j| (i, j)))
+            .filter(|&(i, j)| {
+                let cell: &MazeCell = &self.cells[i][j];
+                !cell.wall
+                    && cell.exit.is_none()
+                    && cell.power_up.is_none()
+                    && (i, j) != self.player_location
+                    && (settings.power_up_placement != Placement::Bands
+                        || distances[i][j].is_some())
+            })
+            .collect();
+        let count: usize = settings.nb_power_ups;
+        if count > candidates.len() {
+            return Err(GenerationError::TooManyPowerUps {
+                requested: count,
+                available: candidates.len(),
+            });
+        }
+        candidates.shuffle(rng);
+        let chosen: Vec<(usize, usize)> = match settings.power_up_placement {
+            Placement::Uniform => candidates[..count].to_vec(),
+            Placement::Spread => spread(&candidates, count),
+            Placement::Bands => {
+                // the shuffle is kept between the cells at the same distance.
+                candidates.sort_by_key(|&(i, j)| distances[i][j]);
+                (0..count)
+                    .map(|band| {
+                        candidates[rng.gen_range(
+                            band * candidates.len() / count..(band + 1) * candidates.len() / count,
+                        )]
+                    })
+                    .collect()
+            }
+        };
+        for (i, j) in chosen {
+            self.cells[i][j].power_up = Some(POWERUPS[rng.sample(&kinds)]);
+        }
+        Ok(())
+    }
+
     /// generate the maze.
-    pub fn new(settings: &Settings) -> Self {
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - the settings of the game.
+    /// * `word_builder` - the automaton of the words used to fill the maze.
+    ///
+    /// # Returns
+    ///
+    /// an error if the settings ask for something that doesn't fit in the maze.
+    pub fn new(settings: &Settings, word_builder: &Matcher) -> Result<Self, GenerationError> {
         let (n, m): (usize, usize) = (settings.height, settings.width);
-        let word_builder: Matcher = Matcher::new(settings.words.clone());
+        let alphabet: Vec<char> = if settings.alphabet.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            settings.alphabet.clone()
+        };
         // generate the rng from the seed.
         let mut rng = StdRng::seed_from_u64(settings.seed);
 
@@ -232,13 +760,16 @@ impl Maze {
             height: n,
             width: m,
             cells: vec![vec![MazeCell::default(); m]; n],
+            exits: vec![],
             player_location: (0, 0),
+            minotaur: None,
         };
         maze.fill_maze_characters(
             (n / 2, m / 2),
             0,
             settings.word_porb,
-            &word_builder,
+            word_builder,
+            &alphabet,
             &mut rng,
         );
 
@@ -250,42 +781,196 @@ impl Maze {
             maze.make_wall(i, j, direction, &mut rng);
             maze.make_wall(i, j, (direction + 4) % 8, &mut rng);
         }
+        maze.repair_pockets(settings.pockets);
 
-        // pick the exit.
-        let i: usize = rng.gen_range(0..maze.height);
-        let j: usize = rng.gen_range(0..maze.width);
-        maze.cells[i][j].wall = false;
-        maze.cells[i][j].exit = true;
-
-        // pick the initial player location and check if it has a path to the exit.
-        let mut x = rng.gen_range(0..maze.height);
-        let mut y = rng.gen_range(0..maze.width);
+        // pick the golden exit among the walkable cells, then a start far enough from it.
+        let walkable: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| (0..m).map(move |j| (i, j)))
+            .filter(|&(i, j)| !maze.cells[i][j].wall)
+            .collect();
+        let &(i, j) = walkable.choose(&mut rng).ok_or(GenerationError::NoStart)?;
+        let (x, y) = maze.pick_start((i, j), &mut rng)?;
+        maze.cells[i][j].exit = Some(ExitKind::Golden);
+        maze.exits.push(Exit {
+            location: (i, j),
+            kind: ExitKind::Golden,
+        });
         maze.player_location = (x, y);
-        while {
-            let route = maze.shortest_route();
-            maze.cells[x][y].exit || route.is_none() || route.unwrap().len() <= (n + m) / 3
-        } {
-            x = rng.gen_range(0..maze.height);
-            y = rng.gen_range(0..maze.width);
-            maze.player_location = (x, y);
-        }
         maze.cells[x][y].visited = true;
-        maze.cells[x][y].wall = false;
-
-        for _ in 0..settings.nb_power_ups {
-            let mut to_up: (usize, usize) =
-                (rng.gen_range(0..maze.height), rng.gen_range(0..maze.width));
-            while maze.cells[to_up.0][to_up.1].wall
-                || maze.cells[to_up.0][to_up.1].exit
-                || maze.cells[to_up.0][to_up.1].power_up.is_some()
-                || to_up == maze.player_location
+
+        // pick the common exit closer to the player than the golden one, but not so close
+        // that its bonus comes for free. small mazes may have none.
+        let distances = maze.distances(maze.player_location);
+        let golden_distance: usize = distances[i][j].unwrap_or_default();
+        let closest: usize = ((n + m) / 6).max(2);
+        let candidates: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| (0..m).map(move |j| (i, j)))
+            .filter(|&(i, j)| {
+                maze.cells[i][j].exit.is_none()
+                    && distances[i][j].is_some_and(|d| {
+                        d >= closest && golden_distance <= 4 * d && 2 * d <= golden_distance
+                    })
+            })
+            .collect();
+        if let Some(&(i, j)) = candidates.choose(&mut rng) {
+            maze.cells[i][j].exit = Some(ExitKind::Common);
+            maze.exits.push(Exit {
+                location: (i, j),
+                kind: ExitKind::Common,
+            });
+        }
+
+        maze.place_power_ups(settings, &mut rng)?;
+
+        // place the minotaur far enough from the player to give them a fair chance.
+        if settings.minotaur != MinotaurBehavior::Off {
+            let candidates: Vec<(usize, usize)> = (0..n)
+                .flat_map(|i| (0..m).map(move |j| (i, j)))
+                .filter(|&(i, j)| {
+                    maze.cells[i][j].exit.is_none()
+                        && distances[i][j].is_some_and(|d| d > (n + m) / 3)
+                })
+                .collect();
+            maze.minotaur = candidates.choose(&mut rng).copied();
+        }
+
+        // turn some of the remaining letters into blanks.
+        let mut candidates: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| (0..m).map(move |j| (i, j)))
+            .filter(|&(i, j)| {
+                let cell: &MazeCell = &maze.cells[i][j];
+                !cell.wall
+                    && cell.exit.is_none()
+                    && cell.power_up.is_none()
+                    && (i, j) != maze.player_location
+            })
+            .collect();
+        if settings.nb_blanks > 0 {
+            candidates.shuffle(&mut rng);
+            for &(i, j) in candidates.iter().take(settings.nb_blanks) {
+                maze.cells[i][j].blank = true;
+            }
+        }
+        Ok(maze)
+    }
+
+    /// finds the shortest walk over unvisited cells that completes a word, starting from
+    /// the states the matcher is in.
+    ///
+    /// # Arguments
+    ///
+    /// * `matcher` - the automaton of the words, in the state reached by the player.
+    ///
+    /// # Returns
+    ///
+    /// the cells to walk over in order and the id of the completed word.
+    pub fn hint(&self, matcher: &Matcher) -> Option<(Vec<(usize, usize)>, usize)> {
+        // every node is a cell, the state reached on it and the node it was reached from.
+        let mut nodes: Vec<((usize, usize), usize, Option<usize>)> = matcher
+            .states()
+            .iter()
+            .map(|&state| (self.player_location, state, None))
+            .collect();
+        let mut seen: BTreeSet<((usize, usize), usize)> = nodes
+            .iter()
+            .map(|&(cell, state, _)| (cell, state))
+            .collect();
+        let mut queue: VecDeque<(usize, usize)> = (0..nodes.len()).map(|node| (node, 0)).collect();
+        let path = |nodes: &[((usize, usize), usize, Option<usize>)], mut node: usize| {
+            let mut cells: Vec<(usize, usize)> = vec![];
+            while let (cell, _, Some(parent)) = nodes[node] {
+                cells.push(cell);
+                node = parent;
+            }
+            cells.reverse();
+            cells
+        };
+
+        while let Some((node, length)) = queue.pop_front() {
+            if length == HINT_LENGTH || seen.len() > HINT_SEARCH {
+                continue;
+            }
+            let (cell, state, _) = nodes[node];
+            let walked: Vec<(usize, usize)> = path(&nodes, node);
+            for direction in 0..8 {
+                let Some(next) = self.valid_coordenates(cell, direction) else {
+                    continue;
+                };
+                let target: &MazeCell = &self.cells[next.0][next.1];
+                if target.visited || target.exit.is_some() || walked.contains(&next) {
+                    continue;
+                }
+                let states: Vec<usize> = if target.blank {
+                    matcher.blank_states(state).collect()
+                } else {
+                    vec![matcher.next_state(state, target.value)]
+                };
+                // the walk has to stay inside a word, going back to the root means giving up on it.
+                for next_state in states {
+                    if next_state == 0 || !seen.insert((next, next_state)) {
+                        continue;
+                    }
+                    nodes.push((next, next_state, Some(node)));
+                    if let Some(word) = matcher.words_at(next_state).next() {
+                        return Some((path(&nodes, nodes.len() - 1), word));
+                    }
+                    queue.push_back((nodes.len() - 1, length + 1));
+                }
+            }
+        }
+        None
+    }
+
+    /// moves the minotaur one step closer to the player following the shortest path.
+    pub fn move_minotaur(&mut self) {
+        let Some((x, y)) = self.minotaur else {
+            return;
+        };
+        let distances = self.distances(self.player_location);
+        let mut best: ((usize, usize), Option<usize>) = ((x, y), distances[x][y]);
+        for direction in 0..8 {
+            if let Some((next_x, next_y)) = self.valid_coordenates((x, y), direction) {
+                if let Some(d) = distances[next_x][next_y] {
+                    if best.1.is_none_or(|best_d| d < best_d) {
+                        best = ((next_x, next_y), Some(d));
+                    }
+                }
+            }
+        }
+        self.minotaur = Some(best.0);
+    }
+}
+
+/// picks cells far from each other, like a poisson-disc sampling: the cells are tried in
+/// order and kept if they are far enough from the kept ones, the distance is lowered until
+/// enough cells are kept.
+///
+/// # Arguments
+///
+/// * `candidates` - the cells that can be picked, in a random order.
+/// * `count` - the number of cells to pick, at most the number of candidates.
+fn spread(candidates: &[(usize, usize)], count: usize) -> Vec<(usize, usize)> {
+    // the spacing the cells would have if they were laid on a grid.
+    let mut radius: usize = ((candidates.len() / count.max(1)) as f64).sqrt() as usize;
+    let mut taken: Vec<bool> = vec![false; candidates.len()];
+    let mut chosen: Vec<(usize, usize)> = vec![];
+    while chosen.len() < count {
+        for (ind, &(i, j)) in candidates.iter().enumerate() {
+            if chosen.len() == count {
+                break;
+            }
+            if !taken[ind]
+                && chosen
+                    .iter()
+                    .all(|&(x, y)| i.abs_diff(x).max(j.abs_diff(y)) >= radius)
             {
-                to_up = (rng.gen_range(0..maze.height), rng.gen_range(0..maze.width));
+                taken[ind] = true;
+                chosen.push((i, j));
             }
-            maze.cells[to_up.0][to_up.1].power_up = Some(*POWERUPS.choose(&mut rng).unwrap());
         }
-        maze
+        radius = radius.saturating_sub(1);
     }
+    chosen
 }
 
 #[derive(Default)]
@@ -293,7 +978,11 @@ pub struct VisibleArea {
     pub cells: Vec<Vec<MazeCell>>,
     pub selected: (usize, usize),
     pub thread: BTreeSet<(i32, i32)>,
+    pub hint: BTreeSet<(i32, i32)>,
+    pub values: BTreeMap<char, usize>,
+    pub minotaur: Option<(i32, i32)>,
     pub offset: (i32, i32),
+    pub palette: Palette,
 }
 
 impl VisibleArea {
@@ -320,21 +1009,52 @@ impl From<&VisibleArea> for Table<'_> {
         let mut cells: Vec<Vec<Cell>> = visible
             .cells
             .iter()
-            .map(|row| row.iter().map(Cell::from).collect::<Vec<Cell>>())
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match visible.values.get(&cell.value) {
+                        Some(&value)
+                            if cell.exit.is_none()
+                                && !cell.wall
+                                && !cell.visited
+                                && !cell.blank
+                                && display_letter(cell.value).width() == 1 =>
+                        {
+                            letter_cell(
+                                cell,
+                                format!("{}{}", display_letter(cell.value), subscript(value)),
+                                &visible.palette,
+                            )
+                        }
+                        _ => maze_cell(cell, &visible.palette),
+                    })
+                    .collect::<Vec<Cell>>()
+            })
             .collect();
+        if let Some((x, y)) = visible.minotaur {
+            let vx: i32 = x - visible.offset.0;
+            let vy: i32 = y - visible.offset.1;
+            if vx >= 0 && vx < n && vy >= 0 && vy < m {
+                cells[vx as usize][vy as usize] = Cell::new(" ♞ ").fg(visible.palette.minotaur);
+            }
+        }
         cells[mid][mid] = Cell::new(" ◎ ");
         cells[visible.selected.0][visible.selected.1] = cells[visible.selected.0]
             [visible.selected.1]
             .clone()
             .reversed();
 
-        for &(x, y) in visible.thread.iter() {
-            let vx: i32 = x - visible.offset.0;
-            let vy: i32 = y - visible.offset.1;
-            if vx >= 0 && vx < n && vy >= 0 && vy < m {
-                let vx: usize = vx as usize;
-                let vy: usize = vy as usize;
-                cells[vx][vy] = cells[vx][vy].clone().bg(Color::Yellow)
+        for (route, color) in [
+            (&visible.thread, visible.palette.thread),
+            (&visible.hint, visible.palette.hint),
+        ] {
+            for &(x, y) in route.iter() {
+                let vx: i32 = x - visible.offset.0;
+                let vy: i32 = y - visible.offset.1;
+                if vx >= 0 && vx < n && vy >= 0 && vy < m {
+                    let vx: usize = vx as usize;
+                    let vy: usize = vy as usize;
+                    cells[vx][vy] = cells[vx][vy].clone().bg(color)
+                }
             }
         }
         let table: Table = cells.iter().map(|row| Row::new(row.clone())).collect();
@@ -342,21 +1062,378 @@ impl From<&VisibleArea> for Table<'_> {
     }
 }
 
-impl From<&MazeCell> for Cell<'_> {
-    fn from(cell: &MazeCell) -> Self {
-        if cell.exit {
-            return Cell::new(" ★ ").fg(Color::Magenta);
+/// creates the cell used to draw a maze cell with the colors of the palette.
+pub fn maze_cell<'a>(cell: &MazeCell, palette: &Palette) -> Cell<'a> {
+    if let Some(kind) = cell.exit {
+        return Cell::new(" ★ ").fg(palette.exit(kind));
+    }
+    if cell.wall {
+        return Cell::new("").bg(palette.wall);
+    }
+    if cell.visited {
+        return Cell::new(" ☐ ");
+    }
+    if cell.blank {
+        return Cell::new(" ✱ ").fg(palette.blank);
+    }
+    letter_cell(cell, display_letter(cell.value), palette)
+}
+
+/// get a printable form of a letter, combining marks are drawn over a dotted circle.
+fn display_letter(c: char) -> String {
+    match c.width() {
+        Some(0) | None => format!("◌{}", c),
+        _ => c.to_string(),
+    }
+}
+
+/// creates the cell of a letter colored by its power up.
+fn letter_cell<'a>(cell: &MazeCell, label: String, palette: &Palette) -> Cell<'a> {
+    if let Some(power) = cell.power_up {
+        Cell::new(Text::from(label).centered()).fg(palette.power_up(power))
+    } else {
+        Cell::new(Text::from(label).centered())
+    }
+}
+
+/// writes a number using subscript digits.
+fn subscript(n: usize) -> String {
+    n.to_string()
+        .chars()
+        .filter_map(|d| d.to_digit(10))
+        .filter_map(|d| char::from_u32('₀' as u32 + d))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::test_utils::test_maze;
+
+    #[test]
+    fn test_hint() {
+        let words: Vec<String> = ["cat", "dog"].iter().map(|&s| s.to_string()).collect();
+        let matcher: Matcher = Matcher::new(words);
+        let maze: Maze = test_maze(&["xc#g", "#azo", "tzzd"], (0, 0), None, &[]);
+        assert_eq!(maze.hint(&matcher), Some((vec![(0, 1), (1, 1), (2, 0)], 0)));
+        let blank: Maze = test_maze(&["xc#g", "#?to", "zzzd"], (0, 0), None, &[]);
+        assert_eq!(
+            blank.hint(&matcher),
+            Some((vec![(0, 1), (1, 1), (1, 2)], 0))
+        );
+        let mut visited: Maze = test_maze(&["xc#g", "#azo", "tzzd"], (0, 0), None, &[]);
+        visited.cells[1][1].visited = true;
+        assert_eq!(visited.hint(&matcher), None);
+    }
+
+    #[test]
+    fn test_components_and_words() {
+        let words: Vec<String> = ["cat", "act", "tact", "dog", "go", "cab"]
+            .iter()
+            .map(|&s| s.to_string())
+            .collect();
+        let matcher: Matcher = Matcher::new(words);
+        let maze: Maze = test_maze(&["ca#g", "#t#o", "###d"], (0, 2), None, &[]);
+        assert_eq!(maze.components(), 2);
+        assert_eq!(maze.words(&matcher), BTreeSet::from([0, 1, 3, 4]));
+        let blank: Maze = test_maze(&["c?", "#t"], (1, 0), None, &[]);
+        assert_eq!(blank.components(), 1);
+        assert_eq!(blank.words(&matcher), BTreeSet::from([0, 1]));
+
+        // a block of blanks can spell any word, the search stays bounded.
+        let rows: Vec<String> = vec!["?".repeat(5); 5];
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        let blanks: Maze = test_maze(&rows, (0, 0), None, &[]);
+        let many: Vec<String> = (0..2000_usize)
+            .map(|n| {
+                (0..4)
+                    .map(|k| (b'a' + (n / 26_usize.pow(k) % 26) as u8) as char)
+                    .collect()
+            })
+            .collect();
+        let many: Matcher = Matcher::new(many);
+        let start: Instant = Instant::now();
+        assert!(!blanks.words(&many).is_empty());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_repair_pockets() {
+        let rows: [&str; 4] = ["ab#cd", "ef#gh", "###ij", "k####"];
+        let mut connected: Maze = test_maze(&rows, (0, 0), None, &[]);
+        assert_eq!(connected.components(), 3);
+        connected.repair_pockets(Pockets::Connect);
+        assert_eq!(connected.components(), 1);
+        let opened: usize = rows.concat().matches('#').count()
+            - connected
+                .cells
+                .iter()
+                .flatten()
+                .filter(|cell| cell.wall)
+                .count();
+        assert_eq!(opened, 2);
+
+        let mut filled: Maze = test_maze(&rows, (0, 0), None, &[]);
+        filled.repair_pockets(Pockets::Fill);
+        assert_eq!(filled.components(), 1);
+        assert!(filled.cells[0][0].wall && filled.cells[3][0].wall);
+        assert!(!filled.cells[0][3].wall && !filled.cells[2][4].wall);
+        assert_eq!(
+            filled
+                .cells
+                .iter()
+                .flatten()
+                .filter(|cell| !cell.wall)
+                .count(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_place_power_ups() {
+        let mut rng: StdRng = StdRng::seed_from_u64(0);
+        let powers = |maze: &Maze| -> Vec<((usize, usize), PowerUP)> {
+            (0..maze.height)
+                .flat_map(|i| (0..maze.width).map(move |j| (i, j)))
+                .filter_map(|(i, j)| maze.cells[i][j].power_up.map(|power| ((i, j), power)))
+                .collect()
+        };
+        let mut settings: Settings = Settings {
+            nb_power_ups: 4,
+            power_up_placement: Placement::Spread,
+            power_up_weights: [0.0, 0.0, 0.0, 1.0, 0.0],
+            ..Default::default()
+        };
+        let mut open: Maze = test_maze(&["abcdefgh"; 8], (0, 0), None, &[]);
+        open.place_power_ups(&settings, &mut rng).unwrap();
+        let placed = powers(&open);
+        assert_eq!(placed.len(), 4);
+        for (ind, &((i, j), power)) in placed.iter().enumerate() {
+            assert_eq!(power, PowerUP::ThorMjolnir);
+            for &((x, y), _) in &placed[ind + 1..] {
+                assert!(i.abs_diff(x).max(j.abs_diff(y)) >= 3);
+            }
         }
-        if cell.wall {
-            return Cell::new("").bg(Color::White);
+
+        settings.nb_power_ups = 2;
+        settings.power_up_placement = Placement::Bands;
+        let mut row: Maze = test_maze(&["abcdefghi"], (0, 0), None, &[]);
+        row.place_power_ups(&settings, &mut rng).unwrap();
+        let columns: Vec<usize> = powers(&row).iter().map(|&((_, j), _)| j).collect();
+        assert!(columns.len() == 2 && (1..=4).contains(&columns[0]) && columns[1] >= 5);
+
+        settings.nb_power_ups = 3;
+        let mut small: Maze = test_maze(&["ab#", "c##"], (0, 0), None, &[]);
+        assert_eq!(
+            small.place_power_ups(&settings, &mut rng),
+            Err(GenerationError::TooManyPowerUps {
+                requested: 3,
+                available: 2
+            })
+        );
+        settings.nb_power_ups = 1;
+        settings.power_up_weights = [0.0; POWERUPS.len()];
+        assert_eq!(
+            small.place_power_ups(&settings, &mut rng),
+            Err(GenerationError::InvalidPowerUpWeights)
+        );
+    }
+
+    #[test]
+    fn test_exits() {
+        assert_eq!(ExitKind::Common.reward(10), 30);
+        assert_eq!(ExitKind::Golden.reward(10), 30);
+        assert_eq!(ExitKind::Golden.reward(usize::MAX), usize::MAX);
+
+        let matcher: Matcher = Matcher::new(vec![]);
+        for seed in 0..20 {
+            let settings: Settings = Settings {
+                height: 20,
+                width: 20,
+                seed,
+                wall_nodes: 3,
+                minotaur: MinotaurBehavior::Off,
+                ..Default::default()
+            };
+            let maze: Maze = Maze::new(&settings, &matcher).unwrap();
+            let distances = maze.distances(maze.player_location);
+            let found: Vec<(ExitKind, usize)> = maze
+                .exits
+                .iter()
+                .map(|exit| {
+                    let (i, j) = exit.location;
+                    assert_eq!(maze.cells[i][j].exit, Some(exit.kind));
+                    (exit.kind, distances[i][j].unwrap())
+                })
+                .collect();
+            let golden: usize = match found[..] {
+                [(ExitKind::Golden, golden)] => golden,
+                [(ExitKind::Golden, golden), (ExitKind::Common, common)] => {
+                    assert!(golden <= 4 * common && 2 * common <= golden);
+                    assert!(common >= 40 / 6);
+                    golden
+                }
+                _ => panic!("unexpected exits {:?}", found),
+            };
+            assert!(golden > 0);
         }
-        if cell.visited {
-            return Cell::new(" ☐ ");
+
+        // the common exit of a small maze is never next to the start.
+        for seed in 0..50 {
+            let settings: Settings = Settings {
+                height: 5,
+                width: 5,
+                seed,
+                minotaur: MinotaurBehavior::Off,
+                ..Default::default()
+            };
+            let maze: Maze = Maze::new(&settings, &matcher).unwrap();
+            let distances = maze.distances(maze.player_location);
+            for exit in maze
+                .exits
+                .iter()
+                .filter(|exit| exit.kind == ExitKind::Common)
+            {
+                let (i, j) = exit.location;
+                assert!(distances[i][j].is_some_and(|d| d >= 2));
+            }
         }
-        if let Some(power) = cell.power_up {
-            Cell::new(Text::from(cell.value.to_string()).centered()).fg(power.color())
-        } else {
-            Cell::new(Text::from(cell.value.to_string()).centered())
+    }
+
+    #[test]
+    fn test_move_minotaur() {
+        let mut open: Maze = test_maze(&["aaaaa"], (0, 0), None, &[]);
+        open.minotaur = Some((0, 4));
+        open.move_minotaur();
+        assert_eq!(open.minotaur, Some((0, 3)));
+
+        // the minotaur goes around the walls.
+        let mut walled: Maze = test_maze(&["a#a", "a#a", "aaa"], (0, 0), None, &[]);
+        walled.minotaur = Some((0, 2));
+        let chase: Vec<(usize, usize)> = (0..4)
+            .filter_map(|_| {
+                walled.move_minotaur();
+                walled.minotaur
+            })
+            .collect();
+        assert_eq!(chase, vec![(1, 2), (2, 1), (1, 0), (0, 0)]);
+
+        let mut cut_off: Maze = test_maze(&["a#a"], (0, 0), None, &[]);
+        cut_off.minotaur = Some((0, 2));
+        cut_off.move_minotaur();
+        assert_eq!(cut_off.minotaur, Some((0, 2)));
+    }
+
+    #[test]
+    fn test_minotaur_placement() {
+        let matcher: Matcher = Matcher::new(vec![]);
+        for seed in 0..10 {
+            let mut settings: Settings = Settings {
+                height: 20,
+                width: 20,
+                seed,
+                wall_nodes: 3,
+                minotaur: MinotaurBehavior::Thief,
+                ..Default::default()
+            };
+            let maze: Maze = Maze::new(&settings, &matcher).unwrap();
+            let (x, y) = maze.minotaur.unwrap();
+            assert!(maze.cells[x][y].exit.is_none());
+            assert!(maze.distances(maze.player_location)[x][y].unwrap() > 40 / 3);
+
+            settings.minotaur = MinotaurBehavior::Off;
+            assert_eq!(Maze::new(&settings, &matcher).unwrap().minotaur, None);
+        }
+    }
+
+    #[test]
+    fn test_start_selection() {
+        let mut rng: StdRng = StdRng::seed_from_u64(0);
+        // the start is at least a third of the sizes of the maze away when possible.
+        let row: Maze = test_maze(&["abcdefgh"], (0, 0), None, &[]);
+        for _ in 0..10 {
+            let (_, j) = row.pick_start((0, 0), &mut rng).unwrap();
+            assert!(j >= 4);
+        }
+        // otherwise it's in the farthest third of the cells.
+        let square: Maze = test_maze(&["abcd", "abcd"], (0, 0), None, &[]);
+        for _ in 0..10 {
+            let (_, j) = square.pick_start((0, 0), &mut rng).unwrap();
+            assert!(j >= 2);
         }
+        let walled: Maze = test_maze(&["a#", "##"], (0, 0), None, &[]);
+        assert_eq!(
+            walled.pick_start((0, 0), &mut rng),
+            Err(GenerationError::NoStart)
+        );
+
+        let settings: Settings = Settings {
+            height: 1,
+            width: 8,
+            minotaur: MinotaurBehavior::Off,
+            ..Default::default()
+        };
+        let maze: Maze = Maze::new(&settings, &Matcher::new(vec![])).unwrap();
+        let route: usize = maze.shortest_route(0).map_or(0, |route| route.len());
+        assert_eq!(
+            route,
+            maze.player_location.1.abs_diff(maze.exits[0].location.1)
+        );
+        assert!(route >= 3);
+    }
+
+    #[test]
+    fn test_display_letter() {
+        let widths: Vec<usize> = ['é', '\u{301}', '字']
+            .map(|c| display_letter(c).width())
+            .to_vec();
+        assert_eq!(widths, vec![1, 1, 2]);
+    }
+
+    #[test]
+    fn test_ariadne_thread() {
+        let matcher: Matcher = Matcher::new(vec!["cat".to_string()]);
+        let with_exit = |rows: &[&str], player: (usize, usize), exit: (usize, usize)| {
+            let mut maze: Maze = test_maze(rows, player, None, &[]);
+            maze.cells[exit.0][exit.1].exit = Some(ExitKind::Golden);
+            maze.exits.push(Exit {
+                location: exit,
+                kind: ExitKind::Golden,
+            });
+            maze
+        };
+        let mut maze: Maze = with_exit(&["zcatz", "zzzzz"], (1, 0), (1, 4));
+        assert_eq!(
+            maze.ariadne_thread(ThreadTarget::Exit(0), &matcher),
+            Some(BTreeSet::from([(0, 1), (0, 2), (0, 3), (1, 4)]))
+        );
+        assert_eq!(maze.ariadne_thread(ThreadTarget::PowerUp, &matcher), None);
+        maze.cells[0][0].power_up = Some(PowerUP::ThorMjolnir);
+        assert_eq!(
+            maze.ariadne_thread(ThreadTarget::PowerUp, &matcher),
+            Some(BTreeSet::from([(0, 0)]))
+        );
+        maze.cells[0][0].visited = true;
+        maze.cells[0][4].power_up = Some(PowerUP::HeliosTorch);
+        assert_eq!(
+            maze.ariadne_thread(ThreadTarget::PowerUp, &matcher),
+            Some(BTreeSet::from([(0, 1), (0, 2), (0, 3), (0, 4)]))
+        );
+        let walled: Maze = with_exit(&["zz#z"], (0, 0), (0, 3));
+        assert_eq!(walled.ariadne_thread(ThreadTarget::Exit(0), &matcher), None);
+
+        // the thread takes two more steps to spell a word on the way.
+        let matcher: Matcher = Matcher::new(vec!["blazer".to_string()]);
+        let detour: Maze = with_exit(&["zblazerz", "z######z", "zzzzzzze"], (2, 0), (2, 7));
+        let thread: BTreeSet<(i32, i32)> = detour
+            .ariadne_thread(ThreadTarget::Exit(0), &matcher)
+            .unwrap();
+        assert_eq!(detour.shortest_route(0).unwrap().len(), 7);
+        assert_eq!(thread.len(), 9);
+        assert!((1..7).all(|j| thread.contains(&(0, j))));
     }
 }
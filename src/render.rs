@@ -0,0 +1,169 @@
+use std::{collections::BTreeSet, fs};
+
+use color_eyre::{eyre::WrapErr, Result};
+use ratatui::style::Color;
+
+use crate::{
+    cache::load_matcher,
+    cli::RenderArgs,
+    level::build_maze,
+    maze::Maze,
+    settings::{MinotaurBehavior, Settings},
+    theme::{Palette, Theme},
+};
+
+/// the size of a cell in pixels.
+const CELL: usize = 24;
+/// the colors of the image, like a dark terminal.
+const BACKGROUND: &str = "#1e1e1e";
+const FOREGROUND: &str = "#d0d0d0";
+
+/// draws the maze of the settings in an SVG image without starting the game.
+pub fn run(settings: &Settings, args: &RenderArgs) -> Result<()> {
+    let matcher = load_matcher(&settings.words, &settings.weights);
+    let mut maze: Maze = build_maze(settings, &matcher)?;
+    if settings.minotaur == MinotaurBehavior::Off {
+        maze.minotaur = None;
+    }
+    let route: BTreeSet<(i32, i32)> = match args.route {
+        true => (0..maze.exits.len())
+            .filter_map(|exit| maze.shortest_route(exit))
+            .flatten()
+            .collect(),
+        false => BTreeSet::new(),
+    };
+    let svg: String = maze_to_svg(&maze, &Theme::from(settings.theme).palette, &route);
+    match &args.output {
+        Some(path) => {
+            fs::write(path, svg).wrap_err_with(|| format!("Failed to write file: {:?}", path))
+        }
+        None => {
+            print!("{}", svg);
+            Ok(())
+        }
+    }
+}
+
+/// draws the whole maze in an SVG image.
+///
+/// # Arguments
+///
+/// * `maze` - the maze to draw.
+/// * `palette` - the colors of the walls, exits, power ups and route.
+/// * `route` - the cells highlighted like Ariadne's thread.
+pub fn maze_to_svg(maze: &Maze, palette: &Palette, route: &BTreeSet<(i32, i32)>) -> String {
+    let (width, height) = (maze.width * CELL, maze.height * CELL);
+    let mut svg: String = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"{}\" \
+         text-anchor=\"middle\" dominant-baseline=\"central\">\n",
+        CELL * 2 / 3
+    );
+    svg.push_str(&format!(
+        "  <rect width=\"{width}\" height=\"{height}\" fill=\"{BACKGROUND}\"/>\n"
+    ));
+    for (i, row) in maze.cells.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            let (x, y) = (j * CELL, i * CELL);
+            if cell.wall && cell.exit.is_none() {
+                svg.push_str(&format!(
+                    "  <rect x=\"{x}\" y=\"{y}\" width=\"{CELL}\" height=\"{CELL}\" fill=\"{}\"/>\n",
+                    css_color(palette.wall)
+                ));
+                continue;
+            }
+            if route.contains(&(i as i32, j as i32)) {
+                svg.push_str(&format!(
+                    "  <rect x=\"{x}\" y=\"{y}\" width=\"{CELL}\" height=\"{CELL}\" fill=\"{}\" fill-opacity=\"0.4\"/>\n",
+                    css_color(palette.thread)
+                ));
+            }
+            let (label, color): (String, String) = if let Some(kind) = cell.exit {
+                ("★".to_string(), css_color(palette.exit(kind)))
+            } else if (i, j) == maze.player_location {
+                ("◎".to_string(), FOREGROUND.to_string())
+            } else if maze.minotaur == Some((i, j)) {
+                ("♞".to_string(), css_color(palette.minotaur))
+            } else if cell.blank {
+                ("✱".to_string(), css_color(palette.blank))
+            } else {
+                let color: String = cell.power_up.map_or(FOREGROUND.to_string(), |power| {
+                    css_color(palette.power_up(power))
+                });
+                (escape(cell.value), color)
+            };
+            svg.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" fill=\"{color}\">{label}</text>\n",
+                x + CELL / 2,
+                y + CELL / 2
+            ));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// escapes the characters that have a meaning in xml.
+fn escape(c: char) -> String {
+    match c {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        c => c.to_string(),
+    }
+}
+
+/// get the css color of a terminal color, named colors use the usual xterm values.
+fn css_color(color: Color) -> String {
+    let (r, g, b): (u8, u8, u8) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        Color::Reset | Color::Indexed(_) => (208, 208, 208),
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{maze::PowerUP, test_utils::test_maze};
+
+    #[test]
+    fn test_svg() {
+        let maze: Maze = test_maze(
+            &["a<#", "?ba"],
+            (0, 0),
+            Some((1, 2)),
+            &[((1, 1), PowerUP::ThorMjolnir)],
+        );
+        let palette: Palette = Palette::default();
+        let route: BTreeSet<(i32, i32)> = maze.shortest_route(0).unwrap();
+        let svg: String = maze_to_svg(&maze, &palette, &route);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(">◎</text>"));
+        assert!(svg.contains(">&lt;</text>"));
+        assert!(svg.contains("fill=\"#ffffff\"/>"));
+        assert!(svg.contains("fill=\"#cdcd00\">★</text>"));
+        assert!(svg.contains("fill=\"#0000ee\">b</text>"));
+        assert!(svg.contains("fill=\"#00cdcd\">✱</text>"));
+        assert_eq!(svg.matches("fill-opacity").count(), route.len());
+    }
+}
@@ -0,0 +1,278 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use clap::ValueEnum;
+
+use crate::settings::Settings;
+
+/// the highest multiplier a combo can reach.
+const MAX_COMBO: usize = 5;
+/// the letter values of the english scrabble.
+const SCRABBLE_VALUES: [(char, usize); 26] = [
+    ('a', 1),
+    ('b', 3),
+    ('c', 3),
+    ('d', 2),
+    ('e', 1),
+    ('f', 4),
+    ('g', 2),
+    ('h', 4),
+    ('i', 1),
+    ('j', 8),
+    ('k', 5),
+    ('l', 1),
+    ('m', 3),
+    ('n', 1),
+    ('o', 1),
+    ('p', 3),
+    ('q', 10),
+    ('r', 1),
+    ('s', 1),
+    ('t', 1),
+    ('u', 1),
+    ('v', 4),
+    ('w', 4),
+    ('x', 8),
+    ('y', 4),
+    ('z', 10),
+];
+
+/// the rule used to give a score to the found words.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ScoringRule {
+    /// the longer the word the higher the score.
+    #[default]
+    Length,
+    /// every letter has the value it has in scrabble.
+    Scrabble,
+    /// the rarer a letter is in the dictionary the more it's worth.
+    Rarity,
+}
+
+/// gives a score to the found words following a scoring rule.
+#[derive(Default)]
+pub struct Scorer {
+    rule: ScoringRule,
+    letter_values: BTreeMap<char, usize>,
+    draupnir: usize,
+    /// the weight of the list of every word.
+    multipliers: Vec<f64>,
+}
+
+impl Scorer {
+    /// creates the scorer and computes the letter values of the rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - the scoring rule.
+    /// * `words` - the dictionary, used to compute the rarity of the letters.
+    /// * `draupnir` - the multiplier of each active Draupnir.
+    pub fn new(rule: ScoringRule, words: &[String], draupnir: usize) -> Self {
+        let letter_values: BTreeMap<char, usize> = match rule {
+            ScoringRule::Length => BTreeMap::new(),
+            ScoringRule::Scrabble => BTreeMap::from(SCRABBLE_VALUES),
+            ScoringRule::Rarity => {
+                let mut frequency: BTreeMap<char, usize> = BTreeMap::new();
+                for c in words.iter().flat_map(|word| word.chars()) {
+                    *frequency.entry(c).or_default() += 1;
+                }
+                let most_common: f64 = frequency.values().max().copied().unwrap_or(1) as f64;
+                frequency
+                    .into_iter()
+                    .map(|(c, f)| {
+                        let value = (most_common / f as f64).log2().round() as usize + 1;
+                        (c, value.min(10))
+                    })
+                    .collect()
+            }
+        };
+        Self {
+            rule,
+            letter_values,
+            draupnir,
+            multipliers: vec![],
+        }
+    }
+
+    /// creates the scorer of a game, the words are weighted by the list they came from.
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            multipliers: settings
+                .origins
+                .iter()
+                .map(|&origin| settings.lists[origin].weight)
+                .collect(),
+            ..Self::new(settings.scoring, &settings.words, settings.draupnir)
+        }
+    }
+
+    /// calculates the score of a word.
+    pub fn score(&self, s: &str) -> usize {
+        let l = s.chars().count() + 2;
+        match self.rule {
+            ScoringRule::Length => l * (l / 3),
+            ScoringRule::Scrabble | ScoringRule::Rarity => {
+                let letters: usize = s.chars().map(|c| self.letter_value(c)).sum();
+                letters * (l / 3)
+            }
+        }
+    }
+
+    /// get the value of a letter, letters unknown to the rule are worth one.
+    pub fn letter_value(&self, c: char) -> usize {
+        self.letter_values.get(&c).copied().unwrap_or(1)
+    }
+
+    /// get the letter values that should be shown on the maze.
+    pub fn shown_values(&self) -> BTreeMap<char, usize> {
+        match self.rule {
+            ScoringRule::Scrabble => self.letter_values.clone(),
+            _ => BTreeMap::new(),
+        }
+    }
+
+    /// get the multiplier given by the active Draupnir rings.
+    pub fn draupnir_bonus(&self, active: usize) -> usize {
+        self.draupnir.saturating_pow(active as u32)
+    }
+
+    /// calculates the score of the words found on a single step.
+    /// the words are scored longest first with the weight of their list and the combo,
+    /// words without a list have a weight of one.
+    ///
+    /// # Arguments
+    ///
+    /// * `found` - the ids of the words found, they are sorted in the order they're scored.
+    /// * `words` - the dictionary.
+    /// * `combo` - the chain of the player, the words are added to it.
+    /// * `draupnir` - the number of active Draupnir rings.
+    pub fn step_score(
+        &self,
+        found: &mut [usize],
+        words: &[String],
+        combo: &mut Combo,
+        draupnir: usize,
+    ) -> usize {
+        found.sort_by_key(|&ind| Reverse(words[ind].chars().count()));
+        let score: usize = found
+            .iter()
+            .map(|&ind| {
+                let word: &str = &words[ind];
+                let multiplier: f64 = self.multipliers.get(ind).copied().unwrap_or(1.0);
+                let score: f64 = self.score(word) as f64 * multiplier;
+                combo.apply(score.round() as usize, word.chars().count())
+            })
+            .fold(0, usize::saturating_add);
+        score.saturating_mul(self.draupnir_bonus(draupnir))
+    }
+}
+
+/// keeps track of the words found in a row without walking over a visited cell.
+#[derive(Default)]
+pub struct Combo {
+    /// number of words found since the chain started.
+    count: usize,
+    /// number of letters collected since the chain started.
+    length: usize,
+    /// the span (in letters since the chain started) of the last word found.
+    last_word: Option<(usize, usize)>,
+}
+
+impl Combo {
+    /// adds a collected letter to the chain.
+    pub fn step(&mut self) {
+        self.length += 1;
+    }
+
+    /// breaks the chain, this happens every time the matcher is reset.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// get the multiplier applied to the next word.
+    pub fn multiplier(&self) -> usize {
+        (self.count + 1).min(MAX_COMBO)
+    }
+
+    /// registers a word ending on the last collected letter.
+    /// words sharing letters with the previous word, like the ones found through the
+    /// fallback links of the matcher, earn half of their score as a bonus.
+    ///
+    /// # Arguments
+    ///
+    /// * `score` - the score of the word on its own.
+    /// * `len` - the number of letters of the word.
+    ///
+    /// # Returns
+    ///
+    /// the score of the word after applying the bonuses.
+    pub fn apply(&mut self, mut score: usize, len: usize) -> usize {
+        let start: usize = (self.length + 1).saturating_sub(len);
+        if self.last_word.is_some_and(|(_, end)| start <= end) {
+            score += score / 2;
+        }
+        score *= self.multiplier();
+        self.count += 1;
+        self.last_word = Some((start, self.length));
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_scoring_rules() {
+        let words: Vec<String> = ["aab", "ab"].iter().map(|&s| s.to_string()).collect();
+        let length: Scorer = Scorer::new(ScoringRule::Length, &words, 8);
+        assert_eq!(length.score("word"), 12);
+        let scrabble: Scorer = Scorer::new(ScoringRule::Scrabble, &words, 8);
+        assert_eq!(scrabble.score("quiz"), 44);
+        let rarity: Scorer = Scorer::new(ScoringRule::Rarity, &words, 8);
+        assert_eq!(rarity.letter_value('a'), 1);
+        assert_eq!(rarity.letter_value('b'), 2);
+        assert_eq!(rarity.draupnir_bonus(2), 64);
+        assert_eq!(rarity.draupnir_bonus(100), usize::MAX);
+    }
+
+    #[test]
+    fn test_step_score() {
+        let words: Vec<String> = ["he", "she"].iter().map(|&s| s.to_string()).collect();
+        let settings: Settings = Settings {
+            words: words.clone(),
+            origins: vec![0, 1],
+            lists: vec!["he".parse().unwrap(), "she:2".parse().unwrap()],
+            draupnir: 2,
+            ..Default::default()
+        };
+        let scorer: Scorer = Scorer::from_settings(&settings);
+        let mut combo: Combo = Combo::default();
+        "she".chars().for_each(|_| combo.step());
+        let mut found: Vec<usize> = vec![0, 1];
+        // "she" is worth 10 with its weight, "he" 12 with the overlap and the combo.
+        assert_eq!(scorer.step_score(&mut found, &words, &mut combo, 1), 44);
+        assert_eq!(found, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_combo_multiplier() {
+        let mut combo: Combo = Combo::default();
+        "cat".chars().for_each(|_| combo.step());
+        assert_eq!(combo.apply(10, 3), 10);
+        "dog".chars().for_each(|_| combo.step());
+        assert_eq!(combo.apply(10, 3), 20);
+        combo.reset();
+        assert_eq!(combo.multiplier(), 1);
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let mut combo: Combo = Combo::default();
+        // "he" is found through the fallback link of "she" and shares its letters.
+        "she".chars().for_each(|_| combo.step());
+        assert_eq!(combo.apply(10, 3), 10);
+        assert_eq!(combo.apply(10, 2), 30);
+    }
+}
@@ -1,27 +1,136 @@
-use crate::cli::Cli;
+use crate::{
+    cli::Cli,
+    dictionary::{read_entries, DictFormat, Dictionary, DictionaryRules},
+    maze::POWERUPS,
+    scoring::ScoringRule,
+    theme::{Theme, ThemeName},
+};
 use clap::ValueEnum;
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
 use rand::random;
-use std::fs::read_to_string;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 use tracing::debug;
+use unicode_normalization::UnicodeNormalization;
 
 const DEFAULT_WORDS: &str = include_str!("../resources/all-words-5200.txt");
 const SIMPLE_WORDS: &str = include_str!("../resources/words-simple-1000.txt");
 const HARD_WORD: &str = include_str!("../resources/long-words-5000.txt");
+const FRENCH_WORDS: &str = include_str!("../resources/french-words.txt");
+const MYTHOLOGY_WORDS: &str = include_str!("../resources/themes/mythology/words.txt");
+const PROGRAMMING_WORDS: &str = include_str!("../resources/themes/programming/words.txt");
+const ANIMALS_WORDS: &str = include_str!("../resources/themes/animals/words.txt");
+/// the word lists bundled with the game and their names.
+const BUNDLED_LISTS: [(&str, &str); 7] = [
+    ("all-words-5200", DEFAULT_WORDS),
+    ("words-simple-1000", SIMPLE_WORDS),
+    ("long-words-5000", HARD_WORD),
+    ("french-words", FRENCH_WORDS),
+    ("mythology", MYTHOLOGY_WORDS),
+    ("programming", PROGRAMMING_WORDS),
+    ("animals", ANIMALS_WORDS),
+];
 
-pub fn parse_words(contents: &str) -> Vec<String> {
-    contents.lines().map(|line| line.to_string()).collect()
+/// a word list and the multiplier applied to the scores of its words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordList {
+    /// the name of a bundled list or the path of a file.
+    pub source: String,
+    pub weight: f64,
 }
 
-#[derive(Default)]
+impl WordList {
+    fn bundled(name: &str) -> Self {
+        Self {
+            source: name.to_string(),
+            weight: 1.0,
+        }
+    }
+
+    /// get the contents of the list if it's bundled with the game.
+    fn bundled_contents(&self) -> Option<&'static str> {
+        BUNDLED_LISTS
+            .iter()
+            .find(|(name, _)| *name == self.source)
+            .map(|(_, contents)| *contents)
+    }
+}
+
+impl FromStr for WordList {
+    type Err = String;
+
+    /// parses "source" or "source:weight".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some((source, weight)) = s.rsplit_once(':') {
+            if let Ok(weight) = weight.parse::<f64>() {
+                if !weight.is_finite() || weight <= 0.0 {
+                    return Err(format!("the weight of {} must be positive", source));
+                }
+                return Ok(Self {
+                    source: source.to_string(),
+                    weight,
+                });
+            }
+        }
+        Ok(Self {
+            source: s.to_string(),
+            weight: 1.0,
+        })
+    }
+}
+
+/// get the sorted set of letters used by the words.
+pub fn parse_alphabet<'a>(words: impl IntoIterator<Item = &'a str>) -> Vec<char> {
+    let letters: BTreeSet<char> = words
+        .into_iter()
+        .flat_map(|word| word.nfc())
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    letters.into_iter().collect()
+}
+
+#[derive(Default, Clone)]
 pub struct Settings {
     pub height: usize,
     pub width: usize,
+    pub lists: Vec<WordList>,
     pub words: Vec<String>,
+    pub weights: Vec<f64>,
+    pub origins: Vec<usize>,
+    pub alphabet: Vec<char>,
     pub seed: u64,
     pub word_porb: f64,
     pub wall_nodes: usize,
+    pub pockets: Pockets,
     pub nb_power_ups: usize,
+    pub power_up_placement: Placement,
+    /// how likely each power up is to be picked, in the order of `POWERUPS`.
+    pub power_up_weights: [f64; POWERUPS.len()],
+    pub nb_blanks: usize,
+    pub hints: usize,
     pub steps: usize,
+    pub minotaur: MinotaurBehavior,
+    pub scoring: ScoringRule,
+    pub draupnir: usize,
+    pub theme: ThemeName,
+    /// the file of a maze to play instead of generating one.
+    pub load_maze: Option<PathBuf>,
+    /// the file where the maze is saved before playing.
+    pub export_maze: Option<PathBuf>,
+    /// the file of the maze opened in the editor instead of playing.
+    pub edit: Option<PathBuf>,
+    /// play the campaign instead of a random maze.
+    pub campaign: bool,
+    /// the summaries of the entries rejected from the lists of the player.
+    pub rejections: Vec<String>,
 }
 
 #[derive(Default, Debug, Clone, ValueEnum)]
@@ -32,6 +141,49 @@ pub enum Difficulty {
     Hard,
 }
 
+/// the language of the bundled words.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+
+/// what happens when the minotaur catches the player.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MinotaurBehavior {
+    /// there is no minotaur in the maze.
+    Off,
+    /// the minotaur steals some steps then rests for a while.
+    #[default]
+    Thief,
+    /// the minotaur ends the game.
+    Deadly,
+}
+
+/// how the power ups are spread over the maze.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Placement {
+    /// any free cell can get a power up.
+    Uniform,
+    /// the power ups are kept as far as possible from each other, like a poisson-disc sampling.
+    #[default]
+    Spread,
+    /// the power ups are evenly placed along the distance from the start.
+    Bands,
+}
+
+/// what happens to the cells the walls cut off from the rest of the maze.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Pockets {
+    /// walls are opened to connect them to the rest of the maze.
+    #[default]
+    Connect,
+    /// they are turned into walls.
+    Fill,
+}
+
 impl Settings {
     fn new(difficulty: Difficulty) -> Self {
         let seed: u64 = random();
@@ -40,37 +192,91 @@ impl Settings {
             Difficulty::Easy => Self {
                 height: 10,
                 width: 10,
-                words: parse_words(SIMPLE_WORDS),
+                lists: vec![WordList::bundled("words-simple-1000")],
+                words: vec![],
+                weights: vec![],
+                origins: vec![],
+                alphabet: vec![],
                 steps: 10,
                 seed,
                 word_porb: 1.0,
                 wall_nodes: 3,
+                pockets: Pockets::Connect,
                 nb_power_ups: 10,
+                power_up_placement: Placement::Bands,
+                power_up_weights: [1.0; POWERUPS.len()],
+                nb_blanks: 10,
+                hints: 5,
+                minotaur: MinotaurBehavior::Off,
+                scoring: ScoringRule::Length,
+                draupnir: 8,
+                theme: ThemeName::Classic,
+                load_maze: None,
+                export_maze: None,
+                edit: None,
+                campaign: false,
+                rejections: vec![],
             },
             Difficulty::Normal => Self {
                 height: 50,
                 width: 50,
                 steps: 20,
-                words: parse_words(DEFAULT_WORDS),
+                lists: vec![WordList::bundled("all-words-5200")],
+                words: vec![],
+                weights: vec![],
+                origins: vec![],
+                alphabet: vec![],
                 seed,
                 word_porb: 1.0,
                 wall_nodes: 10,
+                pockets: Pockets::Connect,
                 nb_power_ups: 40,
+                power_up_placement: Placement::Spread,
+                power_up_weights: [1.0; POWERUPS.len()],
+                nb_blanks: 8,
+                hints: 3,
+                minotaur: MinotaurBehavior::Thief,
+                scoring: ScoringRule::Length,
+                draupnir: 8,
+                theme: ThemeName::Classic,
+                load_maze: None,
+                export_maze: None,
+                edit: None,
+                campaign: false,
+                rejections: vec![],
             },
             Difficulty::Hard => Self {
                 height: 80,
                 width: 80,
                 steps: 20,
-                words: parse_words(HARD_WORD),
+                lists: vec![WordList::bundled("long-words-5000")],
+                words: vec![],
+                weights: vec![],
+                origins: vec![],
+                alphabet: vec![],
                 seed,
                 word_porb: 0.9,
                 wall_nodes: 20,
+                pockets: Pockets::Connect,
                 nb_power_ups: 50,
+                power_up_placement: Placement::Spread,
+                power_up_weights: [1.0; POWERUPS.len()],
+                nb_blanks: 5,
+                hints: 1,
+                minotaur: MinotaurBehavior::Deadly,
+                scoring: ScoringRule::Length,
+                draupnir: 8,
+                theme: ThemeName::Classic,
+                load_maze: None,
+                export_maze: None,
+                edit: None,
+                campaign: false,
+                rejections: vec![],
             },
         }
     }
 
-    pub fn build(args: Cli) -> Self {
+    pub fn build(args: Cli) -> Result<Self> {
         let mut settings: Settings = Settings::new(args.difficulty.unwrap_or(Difficulty::Normal));
         if let Some(s) = args.steps {
             settings.steps = s;
@@ -81,15 +287,101 @@ impl Settings {
         if let Some(w) = args.width {
             settings.width = w;
         }
+        if let Some(placement) = args.placement {
+            settings.power_up_placement = placement;
+        }
+        for (power, weight) in args.power_up_weight {
+            if let Some(ind) = POWERUPS.iter().position(|&p| p == power) {
+                settings.power_up_weights[ind] = weight;
+            }
+        }
+        if let Some(pockets) = args.pockets {
+            settings.pockets = pockets;
+        }
+        if let Some(blanks) = args.blanks {
+            settings.nb_blanks = blanks;
+        }
+        if let Some(hints) = args.hints {
+            settings.hints = hints;
+        }
         if let Some(seed) = args.seed {
             settings.seed = seed;
         }
-        if let Some(path) = args.path {
-            settings.words = parse_words(
-                &read_to_string(path.clone())
-                    .unwrap_or_else(|_| panic!("Failed to read file: {:?}", path)),
+        if let Some(minotaur) = args.minotaur {
+            settings.minotaur = minotaur;
+        }
+        if let Some(scoring) = args.scoring {
+            settings.scoring = scoring;
+        }
+        if let Some(draupnir) = args.draupnir {
+            settings.draupnir = draupnir;
+        }
+        settings.load_maze = args.load_maze;
+        settings.export_maze = args.export_maze;
+        settings.edit = args.edit;
+        settings.campaign = args.campaign;
+        if let Some(theme) = args.theme {
+            settings.theme = theme;
+            if let Some(words) = Theme::from(theme).words {
+                settings.lists = vec![WordList::bundled(words)];
+            }
+        }
+        if args.language == Some(Language::French) {
+            settings.lists = vec![WordList::bundled("french-words")];
+        }
+        if !args.path.is_empty() {
+            settings.lists = args.path;
+        }
+        let declared_alphabet: Option<Vec<char>> = match args.alphabet {
+            Some(path) => Some(parse_alphabet([read_to_string(&path)
+                .wrap_err_with(|| format!("Failed to read file: {:?}", path))?
+                .as_str()])),
+            None => None,
+        };
+
+        // clean the words before building the maze with them.
+        let rules: DictionaryRules = DictionaryRules {
+            min_len: args.min_length.unwrap_or(2),
+            max_len: args.max_length,
+            alphabet: declared_alphabet
+                .as_ref()
+                .map(|alphabet| alphabet.iter().copied().collect()),
+        };
+        let mut dictionaries: Vec<Dictionary> = vec![];
+        for list in settings.lists.iter() {
+            let entries: Vec<(String, Option<f64>)> = match list.bundled_contents() {
+                Some(contents) => DictFormat::Plain.parse(contents)?,
+                None => read_entries(Path::new(&list.source), args.dict_format)?,
+            };
+            let dictionary: Dictionary = Dictionary::new(
+                entries
+                    .iter()
+                    .map(|(word, weight)| (word.as_str(), *weight)),
+                &rules,
             );
+            dictionaries.push(dictionary);
+        }
+        // only the lists of the player are worth a summary, not the bundled ones.
+        Dictionary::reject_duplicates(&mut dictionaries);
+        for (list, dictionary) in settings.lists.iter().zip(dictionaries.iter()) {
+            if let Some(summary) = dictionary
+                .summary()
+                .filter(|_| list.bundled_contents().is_none())
+            {
+                settings
+                    .rejections
+                    .push(format!("{}: {}", list.source, summary));
+            }
+        }
+        let dictionary: Dictionary = Dictionary::merge(dictionaries);
+        if dictionary.words.is_empty() {
+            bail!("The dictionary doesn't contain any valid word.");
         }
-        settings
+        settings.words = dictionary.words;
+        settings.weights = dictionary.weights;
+        settings.origins = dictionary.origins;
+        settings.alphabet = declared_alphabet
+            .unwrap_or_else(|| parse_alphabet(settings.words.iter().map(String::as_str)));
+        Ok(settings)
     }
 }
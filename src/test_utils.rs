@@ -0,0 +1,55 @@
+use crate::maze::{Exit, ExitKind, Maze, MazeCell, PowerUP};
+
+/// builds a maze for the tests from rows of letters, '#' is a wall and '?' a blank.
+/// the maze isn't checked, so it can be unplayable.
+///
+/// # Arguments
+///
+/// * `rows` - the letters of the maze, row by row.
+/// * `start` - the location of the player, it's visited.
+/// * `exit` - the location of the golden exit if there is one.
+/// * `power_ups` - the power ups and their location.
+pub fn test_maze(
+    rows: &[&str],
+    start: (usize, usize),
+    exit: Option<(usize, usize)>,
+    power_ups: &[((usize, usize), PowerUP)],
+) -> Maze {
+    let cells: Vec<Vec<MazeCell>> = rows
+        .iter()
+        .map(|row| {
+            row.chars()
+                .map(|c| match c {
+                    '#' => MazeCell::wall(),
+                    '?' => MazeCell {
+                        blank: true,
+                        ..Default::default()
+                    },
+                    _ => MazeCell {
+                        value: c,
+                        ..Default::default()
+                    },
+                })
+                .collect()
+        })
+        .collect();
+    let mut maze: Maze = Maze {
+        height: rows.len(),
+        width: rows[0].chars().count(),
+        cells,
+        player_location: start,
+        ..Default::default()
+    };
+    maze.cells[start.0][start.1].visited = true;
+    if let Some(location) = exit {
+        maze.cells[location.0][location.1].exit = Some(ExitKind::Golden);
+        maze.exits.push(Exit {
+            location,
+            kind: ExitKind::Golden,
+        });
+    }
+    for &((i, j), power) in power_ups {
+        maze.cells[i][j].power_up = Some(power);
+    }
+    maze
+}
@@ -0,0 +1,171 @@
+use clap::ValueEnum;
+use ratatui::style::Color;
+
+use crate::maze::{ExitKind, PowerUP};
+
+const LOST_MESSAGE: &str = include_str!("../resources/lost_message.txt");
+const WIN_MESSAGE: &str = include_str!("../resources/win_message.txt");
+const MYTHOLOGY_LOST: &str = include_str!("../resources/themes/mythology/lost.txt");
+const MYTHOLOGY_WIN: &str = include_str!("../resources/themes/mythology/win.txt");
+const PROGRAMMING_LOST: &str = include_str!("../resources/themes/programming/lost.txt");
+const PROGRAMMING_WIN: &str = include_str!("../resources/themes/programming/win.txt");
+const ANIMALS_LOST: &str = include_str!("../resources/themes/animals/lost.txt");
+const ANIMALS_WIN: &str = include_str!("../resources/themes/animals/win.txt");
+
+/// the theme packs of the game.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ThemeName {
+    #[default]
+    Classic,
+    Mythology,
+    Programming,
+    Animals,
+}
+
+/// the colors used to draw the maze.
+#[derive(Clone, Copy)]
+pub struct Palette {
+    pub wall: Color,
+    pub common_exit: Color,
+    pub golden_exit: Color,
+    pub thread: Color,
+    pub hint: Color,
+    pub minotaur: Color,
+    pub blank: Color,
+    /// the colors of Ariadne's thread, Helios' torch, Draupnir, Mjolnir and the Bifrost.
+    pub power_ups: [Color; 5],
+}
+
+impl Palette {
+    /// get the color of an exit.
+    pub fn exit(&self, kind: ExitKind) -> Color {
+        match kind {
+            ExitKind::Common => self.common_exit,
+            ExitKind::Golden => self.golden_exit,
+        }
+    }
+
+    /// get the color of a power up.
+    pub fn power_up(&self, power: PowerUP) -> Color {
+        match power {
+            PowerUP::AriadneThread => self.power_ups[0],
+            PowerUP::HeliosTorch => self.power_ups[1],
+            PowerUP::OdinDraupnir => self.power_ups[2],
+            PowerUP::ThorMjolnir => self.power_ups[3],
+            PowerUP::BifrostBridge => self.power_ups[4],
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            wall: Color::White,
+            common_exit: ExitKind::Common.color(),
+            golden_exit: ExitKind::Golden.color(),
+            thread: Color::Yellow,
+            hint: Color::Cyan,
+            minotaur: Color::Red,
+            blank: Color::Cyan,
+            power_ups: [
+                PowerUP::AriadneThread.color(),
+                PowerUP::HeliosTorch.color(),
+                PowerUP::OdinDraupnir.color(),
+                PowerUP::ThorMjolnir.color(),
+                PowerUP::BifrostBridge.color(),
+            ],
+        }
+    }
+}
+
+/// a theme bundles a word list, a palette and the end screens of the game.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    /// the name of the bundled word list of the theme.
+    pub words: Option<&'static str>,
+    pub palette: Palette,
+    pub win_message: &'static str,
+    pub lost_message: &'static str,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::from(ThemeName::Classic)
+    }
+}
+
+impl From<ThemeName> for Theme {
+    fn from(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Classic => Self {
+                words: None,
+                palette: Palette::default(),
+                win_message: WIN_MESSAGE,
+                lost_message: LOST_MESSAGE,
+            },
+            ThemeName::Mythology => Self {
+                words: Some("mythology"),
+                palette: Palette {
+                    wall: Color::Yellow,
+                    common_exit: Color::LightMagenta,
+                    golden_exit: Color::LightYellow,
+                    thread: Color::LightRed,
+                    hint: Color::LightCyan,
+                    minotaur: Color::Red,
+                    blank: Color::LightCyan,
+                    power_ups: [
+                        Color::LightYellow,
+                        Color::LightRed,
+                        Color::LightGreen,
+                        Color::LightBlue,
+                        Color::LightMagenta,
+                    ],
+                },
+                win_message: MYTHOLOGY_WIN,
+                lost_message: MYTHOLOGY_LOST,
+            },
+            ThemeName::Programming => Self {
+                words: Some("programming"),
+                palette: Palette {
+                    wall: Color::Green,
+                    common_exit: Color::Cyan,
+                    golden_exit: Color::LightGreen,
+                    thread: Color::DarkGray,
+                    hint: Color::Blue,
+                    minotaur: Color::LightRed,
+                    blank: Color::Yellow,
+                    power_ups: [
+                        Color::Cyan,
+                        Color::LightRed,
+                        Color::LightGreen,
+                        Color::Blue,
+                        Color::Magenta,
+                    ],
+                },
+                win_message: PROGRAMMING_WIN,
+                lost_message: PROGRAMMING_LOST,
+            },
+            ThemeName::Animals => Self {
+                words: Some("animals"),
+                palette: Palette {
+                    wall: Color::Green,
+                    common_exit: Color::LightMagenta,
+                    golden_exit: Color::Yellow,
+                    thread: Color::LightYellow,
+                    hint: Color::LightBlue,
+                    minotaur: Color::Red,
+                    blank: Color::LightBlue,
+                    power_ups: [
+                        Color::Yellow,
+                        Color::LightRed,
+                        Color::LightCyan,
+                        Color::Blue,
+                        Color::Magenta,
+                    ],
+                },
+                win_message: ANIMALS_WIN,
+                lost_message: ANIMALS_LOST,
+            },
+        }
+    }
+}